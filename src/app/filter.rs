@@ -0,0 +1,76 @@
+use super::Query;
+use crate::todo::{SortMode, Todo};
+use crate::Priority;
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use std::collections::HashSet;
+
+#[derive(Clone)]
+pub enum Filter {
+    All,
+    Active,
+    Completed,
+    HighPriority,
+    DueSoon,
+    /// Open todos that are not waiting on any other open todo.
+    Ready,
+    /// Todos matching a query typed by the user, kept with its source text.
+    Query {
+        source: String,
+        query: Query,
+    },
+    /// A saved view: a named query listed in its own sort order.
+    View {
+        name: String,
+        query: Query,
+        sort: SortMode,
+    },
+}
+
+/// How many days ahead `Filter::DueSoon` looks, counting today.
+pub const DUE_SOON_DAYS: i64 = 3;
+
+/// What filters need to know beyond the todo being checked.
+pub struct FilterContext {
+    pub now: NaiveDateTime,
+    pub today: NaiveDate,
+    open: HashSet<usize>,
+}
+
+impl FilterContext {
+    pub fn new(todos: &[Todo]) -> Self {
+        let now = Local::now().naive_local();
+        FilterContext {
+            now,
+            today: now.date(),
+            open: todos
+                .iter()
+                .filter(|todo| !todo.completed && !todo.is_deleted())
+                .map(|todo| todo.id)
+                .collect(),
+        }
+    }
+
+    /// Whether any of the todos blocking `todo` is still open.
+    pub fn is_blocked(&self, todo: &Todo) -> bool {
+        todo.blocked_by.iter().any(|id| self.open.contains(id))
+    }
+}
+
+impl Filter {
+    pub fn matches(&self, todo: &Todo, context: &FilterContext) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Active => !todo.completed,
+            Filter::Completed => todo.completed,
+            Filter::HighPriority => todo.priority == Priority::High,
+            Filter::DueSoon => {
+                let due_soon = context.today + chrono::Duration::days(DUE_SOON_DAYS - 1);
+                !todo.completed && todo.due.is_some_and(|due| due <= due_soon)
+            }
+            Filter::Ready => !todo.completed && !context.is_blocked(todo),
+            Filter::Query { query, .. } | Filter::View { query, .. } => {
+                query.matches(todo, context)
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use super::App;
+use std::collections::HashSet;
+
+impl App {
+    /// Marks or unmarks the selected todo.
+    pub fn toggle_mark(&mut self) {
+        let Some(id) = self.get_selected_todo().map(|todo| todo.id) else {
+            return;
+        };
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+    }
+
+    /// Starts marking every todo between here and wherever the selection
+    /// moves, or keeps the marked range and stops if already marking.
+    pub fn toggle_range_mark(&mut self) {
+        if self.mark_anchor.is_some() {
+            let marked = self.marked_ids();
+            self.marked.extend(marked);
+            self.mark_anchor = None;
+        } else {
+            self.mark_anchor = self.get_selected_todo().map(|todo| todo.id);
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.mark_anchor = None;
+    }
+
+    /// Drops all marks if there are any, and the search otherwise.
+    pub fn clear_marks_or_search(&mut self) {
+        if self.marked.is_empty() && self.mark_anchor.is_none() {
+            self.clear_search();
+        } else {
+            self.clear_marks();
+        }
+    }
+
+    /// Ids of the marked todos, including the range being marked. Todos
+    /// hidden by the filter, search or folding stay marked but are left out
+    /// until they are shown again, so that bulk actions only touch todos on
+    /// screen.
+    pub fn marked_ids(&self) -> HashSet<usize> {
+        let ids = self.visible_ids();
+        let mut marked: HashSet<usize> = self
+            .marked
+            .iter()
+            .filter(|id| ids.contains(id))
+            .copied()
+            .collect();
+        if let Some(anchor) = self.mark_anchor {
+            let anchor = ids.iter().position(|id| *id == anchor);
+            if let (Some(anchor), Some(row)) = (anchor, self.selected_row()) {
+                marked.extend(&ids[anchor.min(row)..=anchor.max(row)]);
+            }
+        }
+        marked
+    }
+
+    /// The todos an action applies to: the marked ones in list order, or
+    /// else the selected one.
+    pub(super) fn targets(&self) -> Vec<usize> {
+        let marked = self.marked_ids();
+        if marked.is_empty() {
+            return self
+                .get_selected_todo()
+                .map(|todo| todo.id)
+                .into_iter()
+                .collect();
+        }
+        self.todos
+            .iter()
+            .filter(|todo| marked.contains(&todo.id))
+            .map(|todo| todo.id)
+            .collect()
+    }
+}
@@ -1,3 +1,18 @@
+mod filter;
+mod marks;
+mod query;
+mod rows;
+mod search;
 mod state;
+mod tags;
+mod text_input;
+mod trash;
 
-pub use state::{App, Filter, InputMode};
+pub use filter::{Filter, FilterContext, DUE_SOON_DAYS};
+pub use query::{Comparison, Query};
+pub use rows::TodoRow;
+pub use search::match_positions;
+pub use state::{App, InputMode};
+pub use tags::TagBrowser;
+pub use text_input::TextInput;
+pub use trash::Deletion;
@@ -0,0 +1,472 @@
+use super::FilterContext;
+use crate::todo::{parse_due, Todo};
+use crate::Priority;
+use chrono::Local;
+use std::cmp::Ordering;
+
+/// A parsed filter query such as `tag:work pri:high due:<7d !done "text"`.
+///
+/// Terms next to each other must all match; `or` joins alternatives, `!` or
+/// `not` negates the term after it, and parentheses group. The terms are
+/// `tag:NAME`, `pri:high|medium|low`, `due:[<|<=|>|>=]DATE` (where `DATE` is
+/// anything a due date can be set to, or a bare offset such as `7d`),
+/// `due:none`, the keywords `done`, `blocked` and `overdue`, and any other
+/// word or quoted phrase, which is looked for in the description, tags and
+/// notes. Within quotes, `\"` stands for a quote and `\\` for a backslash.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Tag(String),
+    Priority(Priority),
+    /// Todos with a due date comparing to the date as given. The date is
+    /// kept as written and resolved when evaluated, so relative dates stay
+    /// relative to the current day.
+    Due(Comparison, String),
+    NoDue,
+    Done,
+    Blocked,
+    Overdue,
+    Text(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+            Comparison::Greater => ordering.is_gt(),
+        }
+    }
+}
+
+impl Query {
+    /// Parses `input`, returning a description of the problem if it is not a
+    /// valid query.
+    pub fn parse(input: &str) -> Result<Query, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, next: 0 };
+        let query = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(Token::Close) => Err("Unexpected ')'".to_string()),
+            Some(_) => Err("Unexpected input after query".to_string()),
+        }
+    }
+
+    pub fn matches(&self, todo: &Todo, context: &FilterContext) -> bool {
+        match self {
+            Query::And(left, right) => left.matches(todo, context) && right.matches(todo, context),
+            Query::Or(left, right) => left.matches(todo, context) || right.matches(todo, context),
+            Query::Not(query) => !query.matches(todo, context),
+            Query::Tag(tag) => todo.tags.iter().any(|t| t.to_lowercase() == *tag),
+            Query::Priority(priority) => todo.priority == *priority,
+            Query::Due(comparison, date) => match (todo.due, parse_due(date, context.today)) {
+                (Some(due), Some((date, _))) => comparison.accepts(due.cmp(&date)),
+                _ => false,
+            },
+            Query::NoDue => todo.due.is_none(),
+            Query::Done => todo.completed,
+            Query::Blocked => context.is_blocked(todo),
+            Query::Overdue => !todo.completed && todo.is_overdue(context.now),
+            Query::Text(text) => {
+                todo.description.to_lowercase().contains(text)
+                    || todo
+                        .tags
+                        .iter()
+                        .any(|tag| tag.to_lowercase().contains(text))
+                    || todo.notes.to_lowercase().contains(text)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Not,
+    And,
+    Or,
+    /// A word, and whether it was quoted as a whole.
+    Word(String, bool),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '!' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let quoted = c == '"';
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c != '"' {
+                        word.push(c);
+                        continue;
+                    }
+                    // A quoted part may contain spaces and parentheses, and
+                    // may follow a field name as in `tag:"two words"`.
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(c) => word.push(c),
+                                None => return Err("Missing closing quote".to_string()),
+                            },
+                            Some(c) => word.push(c),
+                            None => return Err("Missing closing quote".to_string()),
+                        }
+                    }
+                }
+                tokens.push(match word.to_lowercase().as_str() {
+                    "and" if !quoted => Token::And,
+                    "or" if !quoted => Token::Or,
+                    "not" if !quoted => Token::Not,
+                    _ => Token::Word(word, quoted),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn advance(&mut self) -> Option<&Token> {
+        self.next += 1;
+        self.tokens.get(self.next - 1)
+    }
+
+    fn parse_or(&mut self) -> Result<Query, String> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            query = Query::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query, String> {
+        let mut query = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                None | Some(Token::Or) | Some(Token::Close) => return Ok(query),
+                Some(Token::And) => {
+                    self.advance();
+                }
+                Some(_) => {}
+            }
+            query = Query::And(Box::new(query), Box::new(self.parse_unary()?));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, String> {
+        match self.advance() {
+            Some(Token::Not) => Ok(Query::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let query = self.parse_or()?;
+                match self.advance() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err("Missing ')'".to_string()),
+                }
+            }
+            Some(Token::Word(word, quoted)) => {
+                let (word, quoted) = (word.clone(), *quoted);
+                parse_term(&word, quoted)
+            }
+            Some(Token::Close) => Err("Unexpected ')'".to_string()),
+            Some(Token::And) | Some(Token::Or) => {
+                Err("Expected a term before 'and'/'or'".to_string())
+            }
+            None => Err("Expected a term".to_string()),
+        }
+    }
+}
+
+fn parse_term(word: &str, quoted: bool) -> Result<Query, String> {
+    if quoted {
+        return Ok(Query::Text(word.to_lowercase()));
+    }
+
+    let lower = word.to_lowercase();
+    match lower.as_str() {
+        "done" => return Ok(Query::Done),
+        "blocked" => return Ok(Query::Blocked),
+        "overdue" => return Ok(Query::Overdue),
+        _ => {}
+    }
+
+    let Some((field, value)) = lower.split_once(':') else {
+        return Ok(Query::Text(lower));
+    };
+    match field {
+        "tag" => Ok(Query::Tag(value.to_string())),
+        "pri" | "priority" => match value {
+            "high" | "h" => Ok(Query::Priority(Priority::High)),
+            "medium" | "med" | "m" => Ok(Query::Priority(Priority::Medium)),
+            "low" | "l" => Ok(Query::Priority(Priority::Low)),
+            _ => Err(format!("Unknown priority: {}", value)),
+        },
+        "due" => parse_due_term(value),
+        _ => Err(format!("Unknown field: {}", field)),
+    }
+}
+
+fn parse_due_term(value: &str) -> Result<Query, String> {
+    if value == "none" {
+        return Ok(Query::NoDue);
+    }
+
+    let (comparison, date) = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(prefix, comparison)| value.strip_prefix(prefix).map(|date| (comparison, date)))
+    .unwrap_or((Comparison::Equal, value));
+
+    // The date is resolved again when evaluated; this only checks it.
+    let resolved = with_offset_sign(date);
+    if parse_due(&resolved, Local::now().date_naive()).is_none() {
+        return Err(format!("Unknown due date: {}", date));
+    }
+    Ok(Query::Due(comparison, resolved))
+}
+
+/// `7d` reads naturally after `<`, so offsets may leave out the `+`. Other
+/// dates, including times such as `14:30`, are returned unchanged.
+fn with_offset_sign(date: &str) -> String {
+    let is_offset = date
+        .strip_suffix(['d', 'w', 'm', 'D', 'W', 'M'])
+        .is_some_and(|count| !count.is_empty() && count.bytes().all(|b| b.is_ascii_digit()));
+    if is_offset {
+        format!("+{}", date)
+    } else {
+        date.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::test_util::{date, today, todo};
+
+    fn parse(input: &str) -> Query {
+        Query::parse(input).unwrap_or_else(|e| panic!("{:?} failed to parse: {}", input, e))
+    }
+
+    fn text(word: &str) -> Box<Query> {
+        Box::new(Query::Text(word.to_string()))
+    }
+
+    fn context(todos: &[Todo]) -> FilterContext {
+        let mut context = FilterContext::new(todos);
+        context.today = today();
+        context.now = context.today.and_hms_opt(12, 0, 0).unwrap();
+        context
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse("a or b c"),
+            Query::Or(text("a"), Box::new(Query::And(text("b"), text("c"))))
+        );
+        assert_eq!(
+            parse("a b or c"),
+            Query::Or(Box::new(Query::And(text("a"), text("b"))), text("c"))
+        );
+        assert_eq!(parse("a and b"), Query::And(text("a"), text("b")));
+    }
+
+    #[test]
+    fn parentheses_group() {
+        assert_eq!(
+            parse("(a or b) c"),
+            Query::And(Box::new(Query::Or(text("a"), text("b"))), text("c"))
+        );
+    }
+
+    #[test]
+    fn not_applies_to_the_next_term() {
+        assert_eq!(parse("!done"), Query::Not(Box::new(Query::Done)));
+        assert_eq!(
+            parse("not a b"),
+            Query::And(Box::new(Query::Not(text("a"))), text("b"))
+        );
+        assert_eq!(parse("!!a"), Query::Not(Box::new(Query::Not(text("a")))));
+    }
+
+    #[test]
+    fn quotes_keep_spaces_and_keywords_as_text() {
+        assert_eq!(parse("\"two words\""), Query::Text("two words".to_string()));
+        assert_eq!(parse("\"or\""), Query::Text("or".to_string()));
+        assert_eq!(parse("\"done\""), Query::Text("done".to_string()));
+        assert_eq!(
+            parse("tag:\"two words\""),
+            Query::Tag("two words".to_string())
+        );
+        assert_eq!(parse("\"(a)\""), Query::Text("(a)".to_string()));
+    }
+
+    #[test]
+    fn backslash_escapes_inside_quotes() {
+        assert_eq!(
+            parse(r#""say \"hi\"""#),
+            Query::Text("say \"hi\"".to_string())
+        );
+        assert_eq!(
+            parse(r#"tag:"back\\slash""#),
+            Query::Tag("back\\slash".to_string())
+        );
+        assert_eq!(parse(r"back\slash"), Query::Text("back\\slash".to_string()));
+    }
+
+    #[test]
+    fn fields_are_case_insensitive() {
+        assert_eq!(parse("TAG:Work"), Query::Tag("work".to_string()));
+        assert_eq!(parse("Pri:H"), Query::Priority(Priority::High));
+        assert_eq!(parse("priority:low"), Query::Priority(Priority::Low));
+        assert_eq!(parse("OVERDUE"), Query::Overdue);
+    }
+
+    #[test]
+    fn due_terms() {
+        assert_eq!(
+            parse("due:<7d"),
+            Query::Due(Comparison::Less, "+7d".to_string())
+        );
+        assert_eq!(
+            parse("due:<=fri"),
+            Query::Due(Comparison::LessOrEqual, "fri".to_string())
+        );
+        assert_eq!(
+            parse("due:today"),
+            Query::Due(Comparison::Equal, "today".to_string())
+        );
+        assert_eq!(
+            parse("due:>2026-11-02"),
+            Query::Due(Comparison::Greater, "2026-11-02".to_string())
+        );
+        assert_eq!(
+            parse("due:14:30"),
+            Query::Due(Comparison::Equal, "14:30".to_string())
+        );
+        assert_eq!(parse("due:none"), Query::NoDue);
+    }
+
+    #[test]
+    fn reports_invalid_queries() {
+        let error = |input: &str| Query::parse(input).err();
+        assert_eq!(error("").as_deref(), Some("Expected a term"));
+        assert_eq!(error("(a").as_deref(), Some("Missing ')'"));
+        assert_eq!(error("a)").as_deref(), Some("Unexpected ')'"));
+        assert_eq!(error("\"a").as_deref(), Some("Missing closing quote"));
+        assert_eq!(error("\"a\\").as_deref(), Some("Missing closing quote"));
+        assert_eq!(
+            error("or a").as_deref(),
+            Some("Expected a term before 'and'/'or'")
+        );
+        assert_eq!(error("a or").as_deref(), Some("Expected a term"));
+        assert_eq!(
+            error("pri:urgent").as_deref(),
+            Some("Unknown priority: urgent")
+        );
+        assert_eq!(error("size:big").as_deref(), Some("Unknown field: size"));
+        assert_eq!(
+            error("due:someday").as_deref(),
+            Some("Unknown due date: someday")
+        );
+        assert_eq!(error("due:<7x").as_deref(), Some("Unknown due date: 7x"));
+        assert_eq!(error("due:<3d4").as_deref(), Some("Unknown due date: 3d4"));
+    }
+
+    #[test]
+    fn matches_tags_text_and_notes() {
+        let mut todo = todo(1, "Write Report");
+        todo.tags = vec!["Work".to_string()];
+        todo.notes = "ask Sam".to_string();
+        let context = context(&[]);
+
+        assert!(parse("tag:work").matches(&todo, &context));
+        assert!(!parse("tag:wor").matches(&todo, &context));
+        assert!(parse("report").matches(&todo, &context));
+        assert!(parse("wor").matches(&todo, &context));
+        assert!(parse("sam").matches(&todo, &context));
+        assert!(!parse("!sam").matches(&todo, &context));
+        assert!(parse("missing or tag:work").matches(&todo, &context));
+    }
+
+    #[test]
+    fn matches_due_dates_relative_to_today() {
+        let mut todo = todo(1, "a");
+        todo.due = Some(date(2026, 10, 17));
+        let context = context(&[]);
+
+        assert!(parse("due:<7d").matches(&todo, &context));
+        assert!(!parse("due:<3d").matches(&todo, &context));
+        assert!(parse("due:<=3d").matches(&todo, &context));
+        assert!(parse("due:2026-10-17").matches(&todo, &context));
+        assert!(!parse("due:none").matches(&todo, &context));
+        assert!(!parse("overdue").matches(&todo, &context));
+
+        todo.due = Some(date(2026, 10, 13));
+        assert!(parse("overdue").matches(&todo, &context));
+        todo.completed = true;
+        assert!(!parse("overdue").matches(&todo, &context));
+        assert!(parse("done").matches(&todo, &context));
+    }
+
+    #[test]
+    fn matches_blocked_todos() {
+        let blocker = todo(1, "blocker");
+        let mut blocked = todo(2, "blocked");
+        blocked.blocked_by = vec![1];
+        let context = context(&[blocker.clone(), blocked.clone()]);
+        assert!(parse("blocked").matches(&blocked, &context));
+        assert!(!parse("blocked").matches(&blocker, &context));
+    }
+}
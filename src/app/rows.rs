@@ -0,0 +1,172 @@
+use super::{search, App, FilterContext};
+use crate::todo::{GroupBy, SortMode, Todo};
+use chrono::Local;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// A todo as shown in the list, with its place in the tree of subtasks.
+pub struct TodoRow<'a> {
+    pub todo: &'a Todo,
+    pub depth: usize,
+    /// Completed and total number of subtasks at any depth, if there are any.
+    pub progress: Option<(usize, usize)>,
+    /// Whether its subtasks are hidden.
+    pub collapsed: bool,
+    /// Title of the section the todo is listed under, when grouping.
+    pub group: Option<String>,
+}
+
+impl App {
+    /// Todos that pass the filter, in display order. Each todo is followed
+    /// by its subtasks unless it is collapsed, and siblings are listed in the
+    /// current sort order. A subtask whose parent is
+    /// filtered out is shown under its closest visible ancestor instead, or
+    /// at the top level. While searching, only matching todos are shown and
+    /// collapsed todos are expanded so no match stays hidden.
+    pub fn visible_rows(&self) -> Vec<TodoRow<'_>> {
+        let context = FilterContext::new(&self.todos);
+        let by_id = self.todos_by_id();
+        let query = self.search_query();
+        let shown: HashSet<usize> = self
+            .todos
+            .iter()
+            .filter(|todo| {
+                !todo.is_deleted()
+                    && self.filter.matches(todo, &context)
+                    && search::matches(todo, query)
+            })
+            .map(|todo| todo.id)
+            .collect();
+
+        let mut progress: HashMap<usize, (usize, usize)> = HashMap::new();
+        let mut children: HashMap<Option<usize>, Vec<&Todo>> = HashMap::new();
+        for todo in self.todos.iter().filter(|todo| !todo.is_deleted()) {
+            let ancestors = ancestors(todo, &by_id);
+            for id in &ancestors {
+                let (done, total) = progress.entry(*id).or_default();
+                *total += 1;
+                if todo.completed {
+                    *done += 1;
+                }
+            }
+            if shown.contains(&todo.id) {
+                let parent = ancestors.into_iter().find(|id| shown.contains(id));
+                children.entry(parent).or_default().push(todo);
+            }
+        }
+
+        let sort = self.sort_mode();
+        let sink_completed = self.settings.sink_completed;
+        for siblings in children.values_mut() {
+            // Ties in the manual order keep the order of `todos`, which the
+            // stable sort preserves; other orders fall back to the id.
+            siblings.sort_by(|a, b| {
+                let completed = if sink_completed {
+                    a.completed.cmp(&b.completed)
+                } else {
+                    Ordering::Equal
+                };
+                completed
+                    .then(sort.compare(a, b))
+                    .then(if sort == SortMode::Manual {
+                        Ordering::Equal
+                    } else {
+                        a.id.cmp(&b.id)
+                    })
+            });
+        }
+
+        let mut rows = Vec::new();
+        let expand = !query.is_empty();
+        push_rows(None, 0, expand, &children, &progress, &mut rows);
+        self.group_rows(rows)
+    }
+
+    /// Orders the rows into sections by `settings.group_by`. Each top-level
+    /// todo decides the section for itself and its subtasks.
+    fn group_rows<'a>(&self, rows: Vec<TodoRow<'a>>) -> Vec<TodoRow<'a>> {
+        let group_by = self.settings.group_by;
+        if group_by == GroupBy::None {
+            return rows;
+        }
+
+        let now = Local::now().naive_local();
+        let mut blocks: Vec<((i64, String), Vec<TodoRow<'a>>)> = Vec::new();
+        for row in rows {
+            match blocks.last_mut() {
+                Some((_, block)) if row.depth > 0 => block.push(row),
+                _ => {
+                    let group = group_by.group(row.todo, now).unwrap_or_default();
+                    blocks.push((group, vec![row]));
+                }
+            }
+        }
+        // The sort is stable, so rows keep their order within a section.
+        blocks.sort_by(|(a, _), (b, _)| a.cmp(b));
+        blocks
+            .into_iter()
+            .flat_map(|((_, title), block)| {
+                block.into_iter().map(move |row| TodoRow {
+                    group: Some(title.clone()),
+                    ..row
+                })
+            })
+            .collect()
+    }
+
+    /// Ids of all subtasks of the todo with `id`, at any depth.
+    pub(super) fn descendant_ids(&self, id: usize) -> Vec<usize> {
+        let by_id = self.todos_by_id();
+        self.todos
+            .iter()
+            .filter(|todo| !todo.is_deleted() && ancestors(todo, &by_id).contains(&id))
+            .map(|todo| todo.id)
+            .collect()
+    }
+
+    fn todos_by_id(&self) -> HashMap<usize, &Todo> {
+        self.todos.iter().map(|todo| (todo.id, todo)).collect()
+    }
+}
+
+fn push_rows<'a>(
+    parent: Option<usize>,
+    depth: usize,
+    expand: bool,
+    children: &HashMap<Option<usize>, Vec<&'a Todo>>,
+    progress: &HashMap<usize, (usize, usize)>,
+    rows: &mut Vec<TodoRow<'a>>,
+) {
+    for todo in children.get(&parent).into_iter().flatten() {
+        let collapsed = todo.collapsed && !expand;
+        rows.push(TodoRow {
+            todo,
+            depth,
+            progress: progress.get(&todo.id).copied(),
+            collapsed,
+            group: None,
+        });
+        if !collapsed {
+            push_rows(Some(todo.id), depth + 1, expand, children, progress, rows);
+        }
+    }
+}
+
+/// Ids of the ancestors of `todo`, closest first. A missing parent ends the
+/// chain, and a chain that loops back on itself is treated as empty so the
+/// todo still shows up at the top level.
+fn ancestors(todo: &Todo, by_id: &HashMap<usize, &Todo>) -> Vec<usize> {
+    let mut ancestors = Vec::new();
+    let mut next = todo.parent;
+    while let Some(id) = next {
+        if id == todo.id || ancestors.contains(&id) {
+            return Vec::new();
+        }
+        let Some(parent) = by_id.get(&id) else {
+            break;
+        };
+        ancestors.push(id);
+        next = parent.parent;
+    }
+    ancestors
+}
@@ -0,0 +1,54 @@
+use crate::todo::Todo;
+
+/// Whether `todo` matches the search `query`. The description and tags may
+/// match fuzzily, while notes must contain the query as written, since long
+/// notes would otherwise match almost anything.
+pub fn matches(todo: &Todo, query: &str) -> bool {
+    query.is_empty()
+        || match_positions(&todo.description, query).is_some()
+        || todo
+            .tags
+            .iter()
+            .any(|tag| match_positions(tag, query).is_some())
+        || find_substring(&todo.notes, query).is_some()
+}
+
+/// Positions of the chars in `text` that match `query`, ignoring case. A
+/// substring match is preferred; otherwise the chars of `query` have to
+/// appear in `text` in order, possibly with gaps between them.
+pub fn match_positions(text: &str, query: &str) -> Option<Vec<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    find_substring(text, query).or_else(|| find_fuzzy(text, query))
+}
+
+fn find_substring(text: &str, query: &str) -> Option<Vec<usize>> {
+    let text: Vec<char> = text.chars().collect();
+    let query: Vec<char> = query.chars().collect();
+    if query.is_empty() || query.len() > text.len() {
+        return None;
+    }
+    (0..=text.len() - query.len())
+        .find(|&start| {
+            text[start..]
+                .iter()
+                .zip(&query)
+                .all(|(a, b)| same_char(*a, *b))
+        })
+        .map(|start| (start..start + query.len()).collect())
+}
+
+fn find_fuzzy(text: &str, query: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut text = text.chars().enumerate();
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let (i, _) = text.by_ref().find(|(_, c)| same_char(*c, q))?;
+        positions.push(i);
+    }
+    (!positions.is_empty()).then_some(positions)
+}
+
+fn same_char(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
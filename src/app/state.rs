@@ -1,34 +1,75 @@
-use crate::core::{load_todos, save_todos};
-use crate::todo::Todo;
+use super::{Deletion, Filter, Query, TagBrowser, TextInput, TodoRow};
+use crate::core::{
+    config::{load_settings, load_views, save_settings, save_views, Settings, View},
+    storage, MemoryStorage, Result, Storage,
+};
+use crate::todo::{
+    parse_due, Change, Edit, GroupBy, History, HistoryStep, Recurrence, SortMode, Todo,
+};
 use crate::Priority;
 use chrono::{DateTime, Local};
 use ratatui::style::Color;
+use std::{cell::Cell, collections::HashSet};
 
 #[derive(Clone)]
 pub enum InputMode {
     Normal,
     AddingTodo,
+    EditingTodo,
     AddingNote,
     AddingTags,
+    SettingDue,
+    SettingRecurrence,
+    SettingBlockers,
+    TaggingMarked,
+    MovingTodos,
+    Searching,
+    EnteringQuery,
+    NamingView,
+    BrowsingTags,
+    ConfirmingDelete,
+    BrowsingTrash,
     Help,
 }
 
-#[derive(Clone)]
-pub enum Filter {
-    All,
-    Active,
-    Completed,
-    HighPriority,
-}
-
 pub struct App {
     pub todos: Vec<Todo>,
-    pub input: String,
+    pub next_id: usize,
+    pub input: TextInput,
     pub input_mode: InputMode,
-    pub selected_index: Option<usize>,
+    pub selected_id: Option<usize>,
+    /// Ids of the todos marked for a bulk action.
+    pub marked: HashSet<usize>,
+    /// Where the range being marked started, while marking a range.
+    pub mark_anchor: Option<usize>,
+    pub show_details: bool,
+    pub detail_scroll: u16,
+    /// How far the details pane can scroll at its last rendered size. The
+    /// renderer sets this, as only it knows how the text wraps.
+    pub detail_max_scroll: Cell<u16>,
+    /// Id of a todo whose notes should be opened in an external editor.
+    /// The main loop picks this up, since it owns the terminal.
+    pub editor_request: Option<usize>,
     pub filter: Filter,
+    /// The last search entered; the list is narrowed to its matches.
+    pub search: String,
+    pub tag_browser: TagBrowser,
+    /// A deletion waiting to be confirmed.
+    pub pending_deletion: Option<Deletion>,
+    /// Row of the highlighted todo in the trash browser.
+    pub trash_cursor: usize,
     pub message: Option<(String, Color)>,
     pub message_timeout: Option<DateTime<Local>>,
+    pub settings: Settings,
+    /// Whether changed settings are written back to `config.json`. Only apps
+    /// created with `new` read it in the first place.
+    persist_settings: bool,
+    /// Saved views, listed as extra filter tabs.
+    pub views: Vec<View>,
+    /// Set when `views.json` could not be loaded, so it is not overwritten.
+    views_unreadable: bool,
+    storage: Box<dyn Storage>,
+    history: History,
 }
 
 impl Default for App {
@@ -38,33 +79,118 @@ impl Default for App {
 }
 
 impl App {
+    /// Creates an app backed by the default storage in the user's config
+    /// directory.
     pub fn new() -> App {
-        App {
-            todos: load_todos().unwrap_or_default(),
-            input: String::new(),
+        let mut app = match storage::open_default() {
+            Ok(storage) => App::with_storage(storage),
+            Err(e) => {
+                let mut app = App::with_storage(Box::new(MemoryStorage::default()));
+                app.show_message(&format!("{}; changes will not be saved", e), Color::Red);
+                app
+            }
+        };
+        app.settings = load_settings();
+        app.persist_settings = true;
+        match load_views() {
+            Ok(views) => {
+                let invalid = views
+                    .iter()
+                    .find_map(|view| Query::parse(&view.query).err().map(|e| (&view.name, e)));
+                if let Some((name, e)) = invalid {
+                    let message = format!("View \"{}\" in views.json is invalid: {}", name, e);
+                    app.show_message(&message, Color::Red);
+                }
+                app.views = views;
+            }
+            Err(e) => {
+                app.views_unreadable = true;
+                app.show_message(&format!("Failed to load views: {}", e), Color::Red);
+            }
+        }
+        app
+    }
+
+    pub fn with_storage(mut storage: Box<dyn Storage>) -> App {
+        let (todos, next_id, load_error) = match storage.load() {
+            Ok((todos, next_id)) => (todos, next_id, None),
+            Err(e) => (Vec::new(), 1, Some(e)),
+        };
+        let history = storage.load_history().unwrap_or_default();
+
+        let mut app = App {
+            todos,
+            next_id,
+            input: TextInput::default(),
             input_mode: InputMode::Normal,
-            selected_index: None,
+            selected_id: None,
+            marked: HashSet::new(),
+            mark_anchor: None,
+            show_details: false,
+            detail_scroll: 0,
+            detail_max_scroll: Cell::new(0),
+            editor_request: None,
             filter: Filter::All,
+            search: String::new(),
+            tag_browser: TagBrowser::default(),
+            pending_deletion: None,
+            trash_cursor: 0,
             message: None,
             message_timeout: None,
+            settings: Settings::default(),
+            persist_settings: false,
+            views: Vec::new(),
+            views_unreadable: false,
+            storage,
+            history,
+        };
+        if let Some(e) = load_error {
+            app.show_message(&format!("Failed to load todos: {}", e), Color::Red);
         }
+        app
     }
 
+    /// The todos shown in the list, in display order.
     pub fn filtered_todos(&self) -> Vec<&Todo> {
-        self.todos
-            .iter()
-            .filter(|todo| match self.filter {
-                Filter::All => true,
-                Filter::Active => !todo.completed,
-                Filter::Completed => todo.completed,
-                Filter::HighPriority => todo.priority == Priority::High,
-            })
+        self.visible_rows()
+            .into_iter()
+            .map(|row| row.todo)
             .collect()
     }
 
+    /// Ids of the todos currently visible in the list, in display order.
+    pub(super) fn visible_ids(&self) -> Vec<usize> {
+        self.filtered_todos().iter().map(|todo| todo.id).collect()
+    }
+
+    /// Row of the selected todo within `filtered_todos`, if it is visible.
+    pub fn selected_row(&self) -> Option<usize> {
+        let id = self.selected_id?;
+        self.filtered_todos().iter().position(|todo| todo.id == id)
+    }
+
+    /// Index of the selected todo within `todos`.
+    fn selected_position(&self) -> Option<usize> {
+        self.selected_row()?;
+        let id = self.selected_id?;
+        self.todos.iter().position(|todo| todo.id == id)
+    }
+
+    /// Keeps the selection on a visible todo. If the selected todo is no
+    /// longer visible, the todo now occupying `row` (or the last one) is
+    /// selected instead; without a row the selection is cleared.
+    pub(super) fn sync_selection(&mut self, row: Option<usize>) {
+        let ids = self.visible_ids();
+        if self.selected_id.is_some_and(|id| ids.contains(&id)) {
+            return;
+        }
+        self.selected_id =
+            row.and_then(|row| ids.get(row.min(ids.len().saturating_sub(1))).copied());
+    }
+
     pub fn add_todo(&mut self, description: String) {
         let todo = Todo {
-            id: self.todos.len() + 1,
+            id: self.next_id,
             description,
             completed: false,
             priority: Priority::Medium,
@@ -72,46 +198,377 @@ impl App {
             completed_at: None,
             tags: Vec::new(),
             notes: String::new(),
+            due: None,
+            due_time: None,
+            recurrence: None,
+            parent: None,
+            collapsed: false,
+            blocked_by: Vec::new(),
+            deleted_at: None,
+            position: self
+                .todos
+                .iter()
+                .map(|todo| todo.position + 1)
+                .max()
+                .unwrap_or(0),
+        };
+        self.next_id += 1;
+        self.todos.push(todo.clone());
+        let index = self.todos.len() - 1;
+        let description = format!("add \"{}\"", todo.description);
+        if self.commit(description, vec![Change::Added { index, todo }]) {
+            self.show_message("Todo added successfully!", Color::Green);
+        }
+    }
+
+    /// Replaces the description of the selected todo, keeping everything
+    /// else about it.
+    pub fn edit_todo(&mut self, description: String) {
+        if self.update_selected("edit", |todo| todo.description = description) {
+            self.show_message("Todo updated!", Color::Green);
+        }
+    }
+
+    /// Asks for the notes of the selected todo to be opened in an external
+    /// editor.
+    pub fn request_editor(&mut self) {
+        self.editor_request = self.get_selected_todo().map(|todo| todo.id);
+    }
+
+    /// Replaces the notes of the todo with `id` with text edited outside the
+    /// app.
+    pub fn set_notes(&mut self, id: usize, notes: String) {
+        let Some(todo) = self.todos.iter().find(|todo| todo.id == id) else {
+            return;
         };
-        self.todos.push(todo);
-        save_todos(&self.todos);
-        self.show_message("Todo added successfully!", Color::Green);
+        if todo.notes == notes {
+            self.show_message("Note unchanged", Color::Yellow);
+            return;
+        }
+        let description = format!("edit note of \"{}\"", todo.description);
+        let changes = self
+            .update_todo(id, |todo| todo.notes = notes)
+            .into_iter()
+            .collect();
+        if self.commit(description, changes) {
+            self.show_message("Note updated!", Color::Green);
+        }
     }
 
+    /// Moves the selected todo on to the next priority. Marked todos all
+    /// get the priority following that of the first of them.
     pub fn toggle_priority(&mut self) {
-        if let Some(index) = self.selected_index {
-            if let Some(todo) = self.todos.get_mut(index) {
-                todo.priority = match todo.priority {
-                    Priority::Low => Priority::Medium,
-                    Priority::Medium => Priority::High,
-                    Priority::High => Priority::Low,
-                };
-                save_todos(&self.todos);
-                self.show_message("Priority updated!", Color::Yellow);
-            }
+        let ids = self.targets();
+        let Some(first) = ids
+            .first()
+            .and_then(|id| self.todos.iter().find(|todo| todo.id == *id))
+        else {
+            return;
+        };
+        let priority = match first.priority {
+            Priority::Low => Priority::Medium,
+            Priority::Medium => Priority::High,
+            Priority::High => Priority::Low,
+        };
+        let description = self.describe("change priority of", &ids);
+        let changes = ids
+            .iter()
+            .filter_map(|id| self.update_todo(*id, |todo| todo.priority = priority.clone()))
+            .collect();
+        if self.commit(description, changes) {
+            self.clear_marks();
+            self.show_message("Priority updated!", Color::Yellow);
         }
     }
 
     pub fn add_tags(&mut self, tags: String) {
-        if let Some(index) = self.selected_index {
-            if let Some(todo) = self.todos.get_mut(index) {
-                todo.tags = tags
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-                save_todos(&self.todos);
-                self.show_message("Tags updated!", Color::Green);
-            }
+        let updated = self.update_selected("edit tags of", |todo| {
+            todo.tags = tags
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        });
+        if updated {
+            self.show_message("Tags updated!", Color::Green);
+        }
+    }
+
+    /// Adds tags to, or with a leading `-` removes them from, every marked
+    /// todo, from input such as `work, -home`.
+    pub fn tag_marked(&mut self, input: String) {
+        let ids = self.targets();
+        let (remove, add): (Vec<&str>, Vec<&str>) = input
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .partition(|tag| tag.starts_with('-'));
+        let remove: Vec<String> = remove
+            .iter()
+            .map(|tag| tag[1..].trim().to_lowercase())
+            .collect();
+
+        let description = self.describe("edit tags of", &ids);
+        let changes = ids
+            .iter()
+            .filter_map(|id| {
+                self.update_todo(*id, |todo| {
+                    todo.tags
+                        .retain(|tag| !remove.contains(&tag.to_lowercase()));
+                    for tag in &add {
+                        if !todo
+                            .tags
+                            .iter()
+                            .any(|t| t.to_lowercase() == tag.to_lowercase())
+                        {
+                            todo.tags.push(tag.to_string());
+                        }
+                    }
+                })
+            })
+            .collect();
+        if self.commit(description, changes) {
+            self.clear_marks();
+            self.show_message("Tags updated!", Color::Green);
         }
     }
 
     pub fn add_note(&mut self, note: String) {
-        if let Some(index) = self.selected_index {
-            if let Some(todo) = self.todos.get_mut(index) {
-                todo.notes = note;
-                save_todos(&self.todos);
-                self.show_message("Note added!", Color::Green);
+        if self.update_selected("edit note of", |todo| todo.notes = note) {
+            self.show_message("Note added!", Color::Green);
+        }
+    }
+
+    /// Sets the due date of the selected todo from input such as `fri` or
+    /// `2026-11-02 14:00`; empty input clears it. Returns false if the input
+    /// could not be understood.
+    pub fn set_due(&mut self, input: String) -> bool {
+        let input = input.trim();
+        let due = if input.is_empty() {
+            None
+        } else {
+            match parse_due(input, Local::now().date_naive()) {
+                Some(due) => Some(due),
+                None => {
+                    self.show_message(&format!("Unknown due date: {}", input), Color::Red);
+                    return false;
+                }
+            }
+        };
+
+        let updated = self.update_selected("change due date of", |todo| {
+            todo.due = due.map(|(date, _)| date);
+            todo.due_time = due.and_then(|(_, time)| time);
+        });
+        if updated {
+            self.show_message("Due date updated!", Color::Green);
+        }
+        true
+    }
+
+    /// Sets how the selected todo repeats from input such as `weekly mon,thu`;
+    /// empty input stops it from repeating. Returns false if the input could
+    /// not be understood.
+    pub fn set_recurrence(&mut self, input: String) -> bool {
+        let input = input.trim();
+        let recurrence = if input.is_empty() {
+            None
+        } else {
+            match Recurrence::parse(input, Local::now().date_naive()) {
+                Some(recurrence) => Some(recurrence),
+                None => {
+                    self.show_message(&format!("Unknown repeat rule: {}", input), Color::Red);
+                    return false;
+                }
+            }
+        };
+
+        if self.update_selected("change repeat rule of", |todo| todo.recurrence = recurrence) {
+            self.show_message("Repeat rule updated!", Color::Green);
+        }
+        true
+    }
+
+    /// Sets the todos the selected todo is blocked by from a list of ids such
+    /// as `3, #7`; empty input clears them. Returns false if an id is unknown
+    /// or the blockers would form a cycle.
+    pub fn set_blockers(&mut self, input: String) -> bool {
+        let Some(todo) = self.get_selected_todo() else {
+            return true;
+        };
+        let id = todo.id;
+
+        let mut blockers = Vec::new();
+        for part in input.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let blocker = part.trim_start_matches('#').parse::<usize>().ok();
+            match blocker {
+                Some(blocker)
+                    if self
+                        .todos
+                        .iter()
+                        .any(|todo| todo.id == blocker && !todo.is_deleted()) =>
+                {
+                    if !blockers.contains(&blocker) {
+                        blockers.push(blocker);
+                    }
+                }
+                _ => {
+                    self.show_message(&format!("Unknown todo: {}", part), Color::Red);
+                    return false;
+                }
+            }
+        }
+        if self.creates_cycle(id, &blockers) {
+            self.show_message("A todo cannot end up waiting on itself", Color::Red);
+            return false;
+        }
+
+        if self.update_selected("change blockers of", |todo| todo.blocked_by = blockers) {
+            self.show_message("Blockers updated!", Color::Green);
+        }
+        true
+    }
+
+    /// Whether making the todo with `id` blocked by `blockers` would close a
+    /// cycle, i.e. whether `id` can already be reached from them.
+    fn creates_cycle(&self, id: usize, blockers: &[usize]) -> bool {
+        let mut stack = blockers.to_vec();
+        let mut seen = HashSet::new();
+        while let Some(next) = stack.pop() {
+            if next == id {
+                return true;
+            }
+            if seen.insert(next) {
+                if let Some(todo) = self.todos.iter().find(|todo| todo.id == next) {
+                    stack.extend(&todo.blocked_by);
+                }
+            }
+        }
+        false
+    }
+
+    /// Ids of the todos blocking `todo` that are still open.
+    pub fn open_blockers(&self, todo: &Todo) -> Vec<usize> {
+        todo.blocked_by
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.todos
+                    .iter()
+                    .any(|t| t.id == *id && !t.completed && !t.is_deleted())
+            })
+            .collect()
+    }
+
+    /// Applies `update` to the selected todo and commits the result. The
+    /// edit is described as `action` followed by the todo's description.
+    /// Returns whether the todo was updated and saved.
+    fn update_selected(&mut self, action: &str, update: impl FnOnce(&mut Todo)) -> bool {
+        let Some(todo) = self.get_selected_todo() else {
+            return false;
+        };
+        let description = format!("{} \"{}\"", action, todo.description);
+        let changes = self.update_todo(todo.id, update).into_iter().collect();
+        self.commit(description, changes)
+    }
+
+    /// Applies `update` to the todo with `id` and returns the change to
+    /// pass on to `commit`.
+    pub(super) fn update_todo(
+        &mut self,
+        id: usize,
+        update: impl FnOnce(&mut Todo),
+    ) -> Option<Change> {
+        let todo = self.todos.iter_mut().find(|todo| todo.id == id)?;
+        let before = todo.clone();
+        update(todo);
+        Some(Change::Updated {
+            before: Box::new(before),
+            after: Box::new(todo.clone()),
+        })
+    }
+
+    /// Persists changes that have already been applied to `todos` and
+    /// records them as a single undoable edit. Returns whether saving
+    /// succeeded.
+    pub(super) fn commit(&mut self, description: String, changes: Vec<Change>) -> bool {
+        let result = match changes.as_slice() {
+            [Change::Added { todo, .. }] => self.storage.upsert(todo),
+            [Change::Updated { after, .. }] => self.storage.upsert(after),
+            [Change::Removed { todo, .. }] => self.storage.delete(todo.id),
+            _ => self.storage.save(&self.todos, self.next_id),
+        };
+        self.history.record(Edit {
+            description,
+            changes,
+        });
+        let result = result.and_then(|()| {
+            self.storage
+                .save_history(&self.history, HistoryStep::Recorded)
+        });
+        self.saved(result)
+    }
+
+    /// Reverts the most recent edit.
+    pub fn undo(&mut self) {
+        let row = self.selected_row();
+        let Some(edit) = self.history.undo() else {
+            self.show_message("Nothing to undo", Color::Yellow);
+            return;
+        };
+        for change in edit.changes.iter().rev() {
+            change.inverse().apply(&mut self.todos);
+        }
+        let message = format!("Undone: {}", edit.description);
+        let target = edit_target(edit);
+        self.finish_history_step(HistoryStep::Undone, target, row, &message);
+    }
+
+    /// Applies the most recently undone edit again.
+    pub fn redo(&mut self) {
+        let row = self.selected_row();
+        let Some(edit) = self.history.redo() else {
+            self.show_message("Nothing to redo", Color::Yellow);
+            return;
+        };
+        for change in &edit.changes {
+            change.apply(&mut self.todos);
+        }
+        let message = format!("Redone: {}", edit.description);
+        let target = edit_target(edit);
+        self.finish_history_step(HistoryStep::Redone, target, row, &message);
+    }
+
+    /// Saves the result of an undo or redo and moves the selection to the
+    /// todo it affected.
+    fn finish_history_step(
+        &mut self,
+        step: HistoryStep,
+        target: Option<usize>,
+        row: Option<usize>,
+        message: &str,
+    ) {
+        let result = self
+            .storage
+            .save(&self.todos, self.next_id)
+            .and_then(|()| self.storage.save_history(&self.history, step));
+        if self.saved(result) {
+            self.show_message(message, Color::Green);
+        }
+        if target.is_some() {
+            self.selected_id = target;
+        }
+        self.sync_selection(row);
+    }
+
+    /// Reports a failed storage operation in the message bar.
+    /// Returns whether the operation succeeded.
+    fn saved(&mut self, result: Result<()>) -> bool {
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                self.show_message(&format!("Failed to save todos: {}", e), Color::Red);
+                false
             }
         }
     }
@@ -121,90 +578,734 @@ impl App {
         self.message_timeout = Some(Local::now() + chrono::Duration::seconds(3));
     }
 
+    /// Toggles completion of the selected todo or, if todos are marked, of
+    /// all marked todos: they are completed if any of them is open and
+    /// reopened otherwise.
+    ///
+    /// If enabled in the settings, completing a todo also completes its open
+    /// subtasks.
     pub fn toggle_todo(&mut self) {
-        if let Some(index) = self.selected_index {
-            if let Some(todo) = self.todos.get_mut(index) {
-                todo.completed = !todo.completed;
-                if todo.completed {
-                    todo.completed_at = Some(Local::now());
-                } else {
-                    todo.completed_at = None;
-                }
-                save_todos(&self.todos);
+        let row = self.selected_row();
+        let targets = self.targets();
+        let todos: Vec<&Todo> = self
+            .todos
+            .iter()
+            .filter(|todo| targets.contains(&todo.id))
+            .collect();
+        if todos.is_empty() {
+            return;
+        }
+        let completed = todos.iter().any(|todo| !todo.completed);
+        let action = if completed { "complete" } else { "reopen" };
+        let description = self.describe(action, &targets);
+
+        let blocked: Vec<&Todo> = todos
+            .iter()
+            .copied()
+            .filter(|todo| completed && !self.open_blockers(todo).is_empty())
+            .collect();
+        let warning = match blocked.as_slice() {
+            [] => None,
+            [todo] => Some(format!(
+                "Completed \"{}\" while it is still blocked by {}",
+                todo.description,
+                self.open_blockers(todo)
+                    .iter()
+                    .map(|id| format!("#{}", id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            blocked => Some(format!(
+                "Completed {} todos that are still blocked",
+                blocked.len()
+            )),
+        };
+
+        let mut ids = targets.clone();
+        if completed && self.settings.cascade_completion {
+            for id in &targets {
+                ids.extend(self.descendant_ids(*id));
+            }
+        }
+
+        let mut changes = Vec::new();
+        for id in ids {
+            self.set_completed(id, completed, &mut changes);
+        }
+        if self.commit(description, changes) {
+            self.clear_marks();
+            if let Some(warning) = warning {
+                self.show_message(&warning, Color::Yellow);
+            }
+        }
+        self.sync_selection(row);
+    }
+
+    /// Marks the todo with `id` as completed or open and adds the changes
+    /// to `changes`. Completing a repeating todo spawns its next occurrence
+    /// right after it, and the completed one stays behind as history.
+    fn set_completed(&mut self, id: usize, completed: bool, changes: &mut Vec<Change>) {
+        let Some(index) = self.todos.iter().position(|todo| todo.id == id) else {
+            return;
+        };
+        if self.todos[index].completed == completed {
+            return;
+        }
+
+        let before = self.todos[index].clone();
+        let todo = &mut self.todos[index];
+        todo.completed = completed;
+        if todo.completed {
+            todo.completed_at = Some(Local::now());
+        } else {
+            todo.completed_at = None;
+        }
+        let next = match todo.recurrence.take() {
+            Some(recurrence) if todo.completed => {
+                let today = Local::now().date_naive();
+                let mut next = todo.clone();
+                next.due = recurrence.next_due(todo.due, today);
+                next.recurrence = Some(recurrence);
+                Some(next)
+            }
+            recurrence => {
+                todo.recurrence = recurrence;
+                None
+            }
+        };
+        changes.push(Change::Updated {
+            before: Box::new(before),
+            after: Box::new(todo.clone()),
+        });
+
+        if let Some(mut next) = next {
+            next.id = self.next_id;
+            next.completed = false;
+            next.completed_at = None;
+            next.created_at = Local::now();
+            self.next_id += 1;
+            if let Some(due) = next.due_label() {
+                self.show_message(&format!("Next occurrence due {}", due), Color::Green);
             }
+            self.todos.insert(index + 1, next.clone());
+            changes.push(Change::Added {
+                index: index + 1,
+                todo: next,
+            });
         }
     }
 
+    /// Moves the selected or marked todos to the trash, together with their
+    /// subtasks.
     pub fn delete_todo(&mut self) {
-        if let Some(index) = self.selected_index {
-            self.todos.remove(index);
-            save_todos(&self.todos);
-            if self.selected_index.unwrap() >= self.todos.len() {
-                self.selected_index = if self.todos.is_empty() {
-                    None
-                } else {
-                    Some(self.todos.len() - 1)
-                };
+        let row = self.selected_row();
+        let ids = self.targets();
+        if ids.is_empty() {
+            return;
+        }
+        let description = self.describe("delete", &ids);
+
+        // Everything trashed together shares a timestamp, which is how the
+        // trash tells subtasks that go with their parent.
+        let now = Local::now();
+        let mut trashed = HashSet::new();
+        for id in ids {
+            trashed.insert(id);
+            trashed.extend(self.descendant_ids(id));
+        }
+        let trashed: Vec<usize> = self
+            .todos
+            .iter()
+            .filter(|todo| trashed.contains(&todo.id))
+            .map(|todo| todo.id)
+            .collect();
+        let mut changes = Vec::new();
+        for id in trashed {
+            changes.extend(self.update_todo(id, |t| t.deleted_at = Some(now)));
+        }
+        if self.commit(description, changes) {
+            self.show_message("Moved to the trash; press T to restore", Color::Green);
+        }
+        self.clear_marks();
+        self.selected_id = None;
+        self.sync_selection(row);
+    }
+
+    /// Makes the selected or marked todos subtasks of the todo with the id
+    /// given as input, such as `#3`; empty input moves them to the top
+    /// level. Returns false if the id is unknown or the move would put a
+    /// todo under itself.
+    pub fn move_todos(&mut self, input: String) -> bool {
+        let input = input.trim();
+        let parent = if input.is_empty() {
+            None
+        } else {
+            match input.trim_start_matches('#').parse::<usize>() {
+                Ok(id)
+                    if self
+                        .todos
+                        .iter()
+                        .any(|todo| todo.id == id && !todo.is_deleted()) =>
+                {
+                    Some(id)
+                }
+                _ => {
+                    self.show_message(&format!("Unknown todo: {}", input), Color::Red);
+                    return false;
+                }
+            }
+        };
+
+        let ids = self.targets();
+        if let Some(parent) = parent {
+            let inside = ids
+                .iter()
+                .any(|id| *id == parent || self.descendant_ids(*id).contains(&parent));
+            if inside {
+                self.show_message("Cannot move a todo under itself", Color::Red);
+                return false;
+            }
+        }
+
+        let description = self.describe("move", &ids);
+        let changes = ids
+            .iter()
+            .filter_map(|id| self.update_todo(*id, |todo| todo.parent = parent))
+            .collect();
+        if self.commit(description, changes) {
+            self.clear_marks();
+            self.show_message("Moved!", Color::Green);
+        }
+        true
+    }
+
+    /// Describes an edit of the todos with `ids` for the history, such as
+    /// `delete "Buy milk"` or `delete 3 todos`.
+    pub(super) fn describe(&self, action: &str, ids: &[usize]) -> String {
+        match ids {
+            [id] => {
+                let description = self
+                    .todos
+                    .iter()
+                    .find(|todo| todo.id == *id)
+                    .map(|todo| todo.description.as_str())
+                    .unwrap_or_default();
+                format!("{} \"{}\"", action, description)
             }
+            ids => format!("{} {} todos", action, ids.len()),
         }
     }
 
+    /// Makes the selected todo a subtask of the todo above it at the same
+    /// level.
+    pub fn indent_todo(&mut self) {
+        let rows = self.visible_rows();
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        let depth = rows[row].depth;
+        let parent = rows[..row]
+            .iter()
+            .rev()
+            .take_while(|r| r.depth >= depth)
+            .find(|r| r.depth == depth)
+            .map(|r| r.todo.id);
+        let Some(parent) = parent else {
+            self.show_message("Nothing to indent under", Color::Yellow);
+            return;
+        };
+
+        let Some(todo) = self.get_selected_todo() else {
+            return;
+        };
+        let id = todo.id;
+        let description = format!("indent \"{}\"", todo.description);
+        let mut changes: Vec<Change> = self
+            .update_todo(id, |todo| todo.parent = Some(parent))
+            .into_iter()
+            .collect();
+        if self
+            .todos
+            .iter()
+            .any(|todo| todo.id == parent && todo.collapsed)
+        {
+            changes.extend(self.update_todo(parent, |todo| todo.collapsed = false));
+        }
+        self.commit(description, changes);
+    }
+
+    /// Moves the selected todo out of its parent, next to it.
+    pub fn outdent_todo(&mut self) {
+        let Some(todo) = self.get_selected_todo() else {
+            return;
+        };
+        let Some(parent) = todo.parent else {
+            self.show_message("Already at the top level", Color::Yellow);
+            return;
+        };
+        let grandparent = self
+            .todos
+            .iter()
+            .find(|todo| todo.id == parent)
+            .and_then(|todo| todo.parent);
+        self.update_selected("outdent", |todo| todo.parent = grandparent);
+    }
+
+    /// Moves the selected todo past the next (or previous) visible todo with
+    /// the same parent in the manual sort order.
+    pub fn move_todo(&mut self, down: bool) {
+        if self.sort_mode() != SortMode::Manual {
+            self.show_message("Switch to manual sort with 's' to reorder", Color::Yellow);
+            return;
+        }
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        let sink_completed = self.settings.sink_completed;
+        let rows = self.visible_rows();
+        let selected = rows[row].todo;
+        let depth = rows[row].depth;
+        // Siblings come before anything shallower, which ends the parent.
+        let is_sibling = |other: &&TodoRow| {
+            other.depth == depth && (!sink_completed || other.todo.completed == selected.completed)
+        };
+        let neighbour = if down {
+            rows[row + 1..]
+                .iter()
+                .take_while(|other| other.depth >= depth)
+                .find(is_sibling)
+        } else {
+            rows[..row]
+                .iter()
+                .rev()
+                .take_while(|other| other.depth >= depth)
+                .find(is_sibling)
+        };
+        let Some(neighbour) = neighbour else {
+            return;
+        };
+        let (id, neighbour_id) = (selected.id, neighbour.todo.id);
+        let description = format!("move \"{}\"", selected.description);
+
+        let mut changes = self.spread_positions();
+        let position = |app: &App, id| {
+            app.todos
+                .iter()
+                .find(|todo| todo.id == id)
+                .map_or(0, |todo| todo.position)
+        };
+        let (from, to) = (position(self, id), position(self, neighbour_id));
+        changes.extend(self.update_todo(id, |todo| todo.position = to));
+        changes.extend(self.update_todo(neighbour_id, |todo| todo.position = from));
+        self.commit(description, changes);
+    }
+
+    /// Gives every todo its own position, keeping the manual order, if some
+    /// share one. Returns the changes made.
+    fn spread_positions(&mut self) -> Vec<Change> {
+        let mut order: Vec<(i64, usize)> = self
+            .todos
+            .iter()
+            .map(|todo| (todo.position, todo.id))
+            .collect();
+        order.sort_by_key(|(position, _)| *position);
+        if order.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+            return Vec::new();
+        }
+        order
+            .into_iter()
+            .enumerate()
+            .filter(|(rank, (position, _))| *rank as i64 != *position)
+            .filter_map(|(rank, (_, id))| self.update_todo(id, |todo| todo.position = rank as i64))
+            .collect()
+    }
+
+    /// Shows or hides the subtasks of the selected todo. Folding is not
+    /// recorded in the undo history.
+    pub fn toggle_collapsed(&mut self) {
+        let Some(index) = self.selected_position() else {
+            return;
+        };
+        let id = self.todos[index].id;
+        if !self
+            .todos
+            .iter()
+            .any(|todo| todo.parent == Some(id) && !todo.is_deleted())
+        {
+            return;
+        }
+        self.todos[index].collapsed = !self.todos[index].collapsed;
+        let result = self.storage.upsert(&self.todos[index]);
+        self.saved(result);
+    }
+
+    /// The search narrowing the list: the one being typed, if any, or else
+    /// the last one entered.
+    pub fn search_query(&self) -> &str {
+        if matches!(self.input_mode, InputMode::Searching) {
+            self.input.text()
+        } else {
+            &self.search
+        }
+    }
+
+    pub fn start_search(&mut self) {
+        self.input.set(self.search.clone());
+        self.input_mode = InputMode::Searching;
+    }
+
+    /// Moves the selection to the first match if the typed search hides the
+    /// selected todo.
+    pub fn update_search(&mut self) {
+        self.sync_selection(Some(0));
+    }
+
+    /// Leaves search mode, keeping the typed search in effect if `keep` is
+    /// set and clearing it otherwise.
+    pub fn finish_search(&mut self, keep: bool) {
+        let query = self.input.take();
+        self.search = if keep {
+            query.trim().to_string()
+        } else {
+            String::new()
+        };
+        self.input_mode = InputMode::Normal;
+        self.sync_selection(Some(0));
+    }
+
+    pub fn clear_search(&mut self) {
+        if !self.search.is_empty() {
+            self.search.clear();
+            self.sync_selection(Some(0));
+        }
+    }
+
+    /// Selects the next (or previous) match of the search, wrapping around
+    /// at the ends of the list.
+    pub fn jump_to_match(&mut self, forward: bool) {
+        if self.search.is_empty() {
+            self.show_message("No active search; press / to search", Color::Yellow);
+            return;
+        }
+        let ids = self.visible_ids();
+        if ids.is_empty() {
+            self.show_message(
+                &format!("No matches for \"{}\"", self.search),
+                Color::Yellow,
+            );
+            return;
+        }
+        let len = ids.len();
+        let row = match self.selected_row() {
+            Some(row) if forward => (row + 1) % len,
+            Some(row) => (row + len - 1) % len,
+            None => 0,
+        };
+        self.selected_id = Some(ids[row]);
+        self.detail_scroll = 0;
+    }
+
     pub fn move_selection(&mut self, delta: i32) {
-        let len = self.todos.len();
+        let ids = self.visible_ids();
+        let len = ids.len();
         if len == 0 {
-            self.selected_index = None;
+            self.selected_id = None;
             return;
         }
 
-        let new_index = match self.selected_index {
-            Some(index) => {
+        let new_row = match self.selected_row() {
+            Some(row) => {
                 if delta > 0 {
-                    (index + 1).min(len - 1)
+                    (row + 1).min(len - 1)
                 } else {
-                    index.saturating_sub(1)
+                    row.saturating_sub(1)
                 }
             }
             None => 0,
         };
-        self.selected_index = Some(new_index);
+        self.selected_id = Some(ids[new_row]);
+        self.detail_scroll = 0;
+    }
+
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+        self.detail_scroll = 0;
+    }
+
+    pub fn scroll_details(&mut self, delta: i32) {
+        self.detail_scroll = self
+            .detail_scroll
+            .saturating_add_signed(delta as i16)
+            .min(self.detail_max_scroll.get());
     }
 
     pub fn get_selected_todo(&self) -> Option<&Todo> {
-        self.selected_index.and_then(|i| self.todos.get(i))
+        self.selected_position().and_then(|i| self.todos.get(i))
     }
 
     pub fn get_status_line(&self) -> String {
-        let total = self.todos.len();
-        let completed = self.todos.iter().filter(|t| t.completed).count();
-        let high_priority = self
-            .todos
+        let todos: Vec<&Todo> = self.todos.iter().filter(|t| !t.is_deleted()).collect();
+        let total = todos.len();
+        let completed = todos.iter().filter(|t| t.completed).count();
+        let high_priority = todos
             .iter()
             .filter(|t| matches!(t.priority, Priority::High))
             .count();
 
-        format!(
+        let mut status = format!(
             "Total: {} | Completed: {} | Pending: {} | High Priority: {} | Filter: {}",
             total,
             completed,
             total - completed,
             high_priority,
-            match self.filter {
+            match &self.filter {
                 Filter::All => "All",
                 Filter::Active => "Active",
                 Filter::Completed => "Completed",
                 Filter::HighPriority => "High Priority",
+                Filter::DueSoon => "Due Soon",
+                Filter::Ready => "Ready",
+                Filter::Query { source, .. } => source,
+                Filter::View { name, .. } => name,
             }
-        )
+        );
+        status.push_str(&format!(" | Sort: {}", self.sort_mode().label()));
+        let marked = self.marked_ids().len();
+        if marked > 0 {
+            status.push_str(&format!(" | Marked: {}", marked));
+        }
+        if self.settings.group_by != GroupBy::None {
+            status.push_str(&format!(" | Group: {}", self.settings.group_by.label()));
+        }
+        let query = self.search_query();
+        if !query.is_empty() {
+            status.push_str(&format!(
+                " | Search: \"{}\" ({} found)",
+                query,
+                self.visible_ids().len()
+            ));
+        }
+        status
+    }
+
+    /// Text to start the filter prompt with: the current query, if any.
+    pub fn query_source(&self) -> String {
+        match &self.filter {
+            Filter::Query { source, .. } => source.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// Filters the list by a query such as `tag:work !done`; empty input
+    /// shows all todos again. Returns false if the query could not be
+    /// parsed.
+    pub fn apply_query(&mut self, input: String) -> bool {
+        let source = input.trim().to_string();
+        if source.is_empty() {
+            self.filter = Filter::All;
+        } else {
+            match Query::parse(&source) {
+                Ok(query) => self.filter = Filter::Query { source, query },
+                Err(e) => {
+                    self.show_message(&format!("Invalid query: {}", e), Color::Red);
+                    return false;
+                }
+            }
+        }
+        self.sync_selection(Some(0));
+        true
     }
 
+    /// Moves on to the next filter tab: the built-in filters, then the saved
+    /// views.
     pub fn cycle_filter(&mut self) {
-        self.filter = match self.filter {
+        self.filter = match &self.filter {
             Filter::All => Filter::Active,
             Filter::Active => Filter::Completed,
             Filter::Completed => Filter::HighPriority,
-            Filter::HighPriority => Filter::All,
+            Filter::HighPriority => Filter::DueSoon,
+            Filter::DueSoon => Filter::Ready,
+            Filter::Ready => self.view_filter(0),
+            Filter::View { name, .. } => {
+                let index = self.views.iter().position(|view| view.name == *name);
+                self.view_filter(index.map_or(0, |index| index + 1))
+            }
+            Filter::Query { .. } => Filter::All,
+        };
+        self.sync_selection(None);
+    }
+
+    /// The filter for the first saved view from `index` on whose query
+    /// parses, or `Filter::All` if there is none. Views passed over because
+    /// their query does not parse are reported.
+    fn view_filter(&mut self, index: usize) -> Filter {
+        let mut invalid = None;
+        let mut filter = Filter::All;
+        for view in self.views.iter().skip(index) {
+            match Query::parse(&view.query) {
+                Ok(query) => {
+                    filter = Filter::View {
+                        name: view.name.clone(),
+                        query,
+                        sort: view.sort,
+                    };
+                    break;
+                }
+                Err(e) => {
+                    invalid.get_or_insert((view.name.clone(), e));
+                }
+            }
+        }
+        if let Some((name, e)) = invalid {
+            self.show_message(&format!("Skipped view \"{}\": {}", name, e), Color::Red);
+        }
+        filter
+    }
+
+    /// The order the list is sorted in: that of the view shown, if any, or
+    /// else the one from the settings.
+    pub fn sort_mode(&self) -> SortMode {
+        match &self.filter {
+            Filter::View { sort, .. } => *sort,
+            _ => self.settings.sort,
+        }
+    }
+
+    /// Switches to the next sort order and remembers it, in the view shown
+    /// if there is one and in the settings otherwise.
+    pub fn cycle_sort(&mut self) {
+        let sort = self.sort_mode().next();
+        let saved = if let Filter::View {
+            name,
+            sort: view_sort,
+            ..
+        } = &mut self.filter
+        {
+            *view_sort = sort;
+            let name = name.clone();
+            if let Some(view) = self.views.iter_mut().find(|view| view.name == name) {
+                view.sort = sort;
+            }
+            self.views_unreadable || self.views_saved()
+        } else {
+            self.settings.sort = sort;
+            !self.persist_settings || self.settings_saved()
+        };
+        if saved {
+            self.show_message(&format!("Sorted by {}", sort.label()), Color::Green);
+        }
+    }
+
+    /// Switches to the next way of grouping the list and remembers it.
+    pub fn cycle_group(&mut self) {
+        self.settings.group_by = self.settings.group_by.next();
+        if !self.persist_settings || self.settings_saved() {
+            let label = self.settings.group_by.label();
+            self.show_message(&format!("Grouped by {}", label), Color::Green);
+        }
+    }
+
+    fn settings_saved(&mut self) -> bool {
+        match save_settings(&self.settings) {
+            Ok(()) => true,
+            Err(e) => {
+                self.show_message(&format!("Failed to save settings: {}", e), Color::Red);
+                false
+            }
+        }
+    }
+
+    /// Text to start the view name prompt with: the name of the current
+    /// view, if any.
+    pub fn view_name(&self) -> String {
+        match &self.filter {
+            Filter::View { name, .. } => name.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// Saves the current query and sort order as a view called `name`,
+    /// replacing any view with that name. Returns false if there is nothing
+    /// to save or the name is empty.
+    pub fn save_view(&mut self, name: String) -> bool {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            self.show_message("A view needs a name", Color::Red);
+            return false;
+        }
+        let (query, source) = match &self.filter {
+            Filter::Query { source, query } => (query.clone(), source.clone()),
+            Filter::View { name, query, .. } => {
+                let Some(view) = self.views.iter().find(|view| view.name == *name) else {
+                    return false;
+                };
+                (query.clone(), view.query.clone())
+            }
+            _ => {
+                self.show_message("Enter a query with 'f' before saving a view", Color::Red);
+                return false;
+            }
+        };
+        if self.views_unreadable {
+            self.show_message(
+                "Refusing to overwrite views.json, which could not be loaded",
+                Color::Red,
+            );
+            return false;
+        }
+
+        let view = View {
+            name: name.clone(),
+            query: source,
+            sort: self.sort_mode(),
+        };
+        let sort = view.sort;
+        // Saving a shown view under a new name renames it in place.
+        let current = match &self.filter {
+            Filter::View { name, .. } => Some(name.clone()),
+            _ => None,
+        };
+        if current.as_ref() != Some(&name) {
+            self.views.retain(|view| view.name != name);
+        }
+        match current.and_then(|current| self.views.iter_mut().find(|view| view.name == current)) {
+            Some(existing) => *existing = view,
+            None => self.views.push(view),
+        }
+        self.filter = Filter::View {
+            name: name.clone(),
+            query,
+            sort,
+        };
+        if self.views_saved() {
+            self.show_message(&format!("Saved view \"{}\"", name), Color::Green);
+        }
+        true
+    }
+
+    /// Deletes the saved view currently shown and goes back to all todos.
+    pub fn delete_view(&mut self) {
+        let Filter::View { name, .. } = &self.filter else {
+            self.show_message("Only saved views can be deleted", Color::Yellow);
+            return;
         };
-        self.selected_index = None;
+        let name = name.clone();
+        self.views.retain(|view| view.name != name);
+        self.filter = Filter::All;
+        self.sync_selection(None);
+        if self.views_saved() {
+            self.show_message(&format!("Deleted view \"{}\"", name), Color::Green);
+        }
+    }
+
+    fn views_saved(&mut self) -> bool {
+        match save_views(&self.views) {
+            Ok(()) => true,
+            Err(e) => {
+                self.show_message(&format!("Failed to save views: {}", e), Color::Red);
+                false
+            }
+        }
     }
 
     pub fn update(&mut self) {
@@ -216,3 +1317,84 @@ impl App {
         }
     }
 }
+
+/// Id of the todo an edit is about, used to select it after undo or redo.
+fn edit_target(edit: &Edit) -> Option<usize> {
+    edit.changes.first().map(|change| match change {
+        Change::Added { todo, .. } | Change::Removed { todo, .. } => todo.id,
+        Change::Updated { after, .. } => after.id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::storage::MemoryStorage;
+    use crate::todo::test_util::todo;
+
+    /// An app over todos 1 (completed), 2 and 3, with nothing selected.
+    fn app() -> App {
+        let mut done = todo(1, "done");
+        done.completed = true;
+        let todos = vec![done, todo(2, "second"), todo(3, "third")];
+        App::with_storage(Box::new(MemoryStorage::new(todos)))
+    }
+
+    fn find(app: &App, id: usize) -> &Todo {
+        app.todos.iter().find(|todo| todo.id == id).unwrap()
+    }
+
+    #[test]
+    fn actions_hit_the_visible_row() {
+        let mut app = app();
+        app.cycle_filter();
+        assert!(matches!(app.filter, Filter::Active));
+        app.move_selection(1);
+        app.move_selection(1);
+        assert_eq!(app.selected_row(), Some(1));
+
+        app.toggle_priority();
+        assert_ne!(find(&app, 3).priority, Priority::Medium);
+        assert_eq!(find(&app, 2).priority, Priority::Medium);
+    }
+
+    #[test]
+    fn move_selection_stays_within_the_visible_rows() {
+        let mut app = app();
+        app.cycle_filter();
+        for _ in 0..5 {
+            app.move_selection(1);
+        }
+        assert_eq!(app.selected_id, Some(3));
+        for _ in 0..5 {
+            app.move_selection(-1);
+        }
+        assert_eq!(app.selected_id, Some(2));
+    }
+
+    #[test]
+    fn changing_the_filter_keeps_a_visible_selection() {
+        let mut app = app();
+        app.move_selection(1);
+        app.move_selection(1);
+        assert_eq!(app.selected_id, Some(2));
+
+        app.cycle_filter();
+        assert_eq!(app.selected_row(), Some(0));
+        app.cycle_filter();
+        assert!(matches!(app.filter, Filter::Completed));
+        assert_eq!(app.selected_id, None);
+    }
+
+    #[test]
+    fn completing_a_todo_selects_the_next_visible_one() {
+        let mut app = app();
+        app.cycle_filter();
+        app.move_selection(1);
+        assert_eq!(app.selected_id, Some(2));
+
+        app.toggle_todo();
+        assert!(find(&app, 2).completed);
+        assert_eq!(app.selected_id, Some(3));
+    }
+}
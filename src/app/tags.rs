@@ -0,0 +1,140 @@
+use super::{App, InputMode};
+use ratatui::style::Color;
+use std::collections::BTreeMap;
+
+/// State of the tag browser popup.
+#[derive(Default)]
+pub struct TagBrowser {
+    /// Row of the highlighted tag.
+    pub cursor: usize,
+    /// Tags picked so far, in lowercase.
+    pub marked: Vec<String>,
+    /// Whether todos must have all marked tags rather than any of them.
+    pub match_all: bool,
+}
+
+impl App {
+    /// Every tag in use with the number of todos carrying it, sorted by
+    /// name. Tags differing only in case are counted together under the
+    /// first spelling found.
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<String, (String, usize)> = BTreeMap::new();
+        for todo in self.todos.iter().filter(|todo| !todo.is_deleted()) {
+            for tag in &todo.tags {
+                counts
+                    .entry(tag.to_lowercase())
+                    .or_insert_with(|| (tag.clone(), 0))
+                    .1 += 1;
+            }
+        }
+        counts.into_values().collect()
+    }
+
+    pub fn open_tag_browser(&mut self) {
+        if self.tag_counts().is_empty() {
+            self.show_message("No tags yet; add some with 't'", Color::Yellow);
+            return;
+        }
+        self.tag_browser = TagBrowser::default();
+        self.input_mode = InputMode::BrowsingTags;
+    }
+
+    pub fn move_tag_cursor(&mut self, delta: i32) {
+        let len = self.tag_counts().len();
+        let cursor = self.tag_browser.cursor as i64 + i64::from(delta);
+        self.tag_browser.cursor = cursor.clamp(0, len.saturating_sub(1) as i64) as usize;
+    }
+
+    /// Marks or unmarks the highlighted tag.
+    pub fn toggle_tag_mark(&mut self) {
+        let Some((tag, _)) = self.tag_counts().into_iter().nth(self.tag_browser.cursor) else {
+            return;
+        };
+        let tag = tag.to_lowercase();
+        let marked = &mut self.tag_browser.marked;
+        match marked.iter().position(|t| *t == tag) {
+            Some(i) => {
+                marked.remove(i);
+            }
+            None => marked.push(tag),
+        }
+    }
+
+    /// Filters the list by the marked tags, or by the highlighted one if
+    /// none are marked, and closes the browser.
+    pub fn apply_tag_filter(&mut self) {
+        let mut tags = self.tag_browser.marked.clone();
+        if tags.is_empty() {
+            tags.extend(
+                self.tag_counts()
+                    .into_iter()
+                    .nth(self.tag_browser.cursor)
+                    .map(|(tag, _)| tag.to_lowercase()),
+            );
+        }
+        let terms: Vec<String> = tags
+            .iter()
+            .map(|tag| {
+                if tag.contains(|c: char| c.is_whitespace() || "()!\"\\".contains(c)) {
+                    format!("tag:\"{}\"", tag.replace('\\', "\\\\").replace('"', "\\\""))
+                } else {
+                    format!("tag:{}", tag)
+                }
+            })
+            .collect();
+        let separator = if self.tag_browser.match_all {
+            " "
+        } else {
+            " or "
+        };
+        self.input_mode = InputMode::Normal;
+        self.apply_query(terms.join(separator));
+    }
+
+    /// Completes the tag being typed in the tags input from the tags already
+    /// in use. A single candidate is completed in full; with several, the
+    /// common prefix is filled in and the candidates are listed.
+    pub fn complete_tag(&mut self) {
+        let text = self.input.text().to_string();
+        let (head, fragment) = match text.rfind(',') {
+            Some(i) => text.split_at(i + 1),
+            None => ("", text.as_str()),
+        };
+        let fragment = fragment.trim_start().to_lowercase();
+        let entered: Vec<String> = head
+            .split(',')
+            .map(|tag| tag.trim().to_lowercase())
+            .collect();
+        let candidates: Vec<String> = self
+            .tag_counts()
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .filter(|tag| {
+                let lower = tag.to_lowercase();
+                lower.starts_with(&fragment) && !entered.contains(&lower)
+            })
+            .collect();
+
+        let separator = if head.is_empty() { "" } else { " " };
+        let completed = match candidates.as_slice() {
+            [] => return,
+            [tag] => format!("{}{}{}, ", head, separator, tag),
+            [first, rest @ ..] => {
+                let prefix = rest.iter().fold(first.clone(), |prefix, tag| {
+                    prefix
+                        .chars()
+                        .zip(tag.chars())
+                        .take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+                        .map(|(a, _)| a)
+                        .collect()
+                });
+                self.show_message(&candidates.join(", "), Color::Cyan);
+                if prefix.chars().count() <= fragment.chars().count() {
+                    return;
+                }
+                format!("{}{}{}", head, separator, prefix)
+            }
+        };
+        self.input.set(completed);
+    }
+}
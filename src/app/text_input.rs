@@ -0,0 +1,386 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Editable text with a cursor, shared by all input modes.
+///
+/// The cursor is a byte offset that always sits on a grapheme boundary, so
+/// editing never splits a character made of several code points. A
+/// multi-line input also accepts Enter as a line break and Up/Down to move
+/// between lines; line-based keys such as Home act on the cursor's line.
+#[derive(Default, Clone)]
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    multiline: bool,
+}
+
+impl TextInput {
+    /// A multi-line input holding `text`, with the cursor at its end.
+    pub fn multiline(text: impl Into<String>) -> Self {
+        let text = text.into();
+        TextInput {
+            cursor: text.len(),
+            text,
+            multiline: true,
+        }
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Replaces the text and moves the cursor to its end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    /// Empties the input and makes it single-line again.
+    pub fn clear(&mut self) {
+        *self = TextInput::default();
+    }
+
+    /// Takes the text out, leaving the input empty and single-line.
+    pub fn take(&mut self) -> String {
+        std::mem::take(self).text
+    }
+
+    /// Line of the cursor, and the display width of the text before it on
+    /// that line.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let line = self.text[..self.cursor].matches('\n').count();
+        let column = self.text[self.line_start()..self.cursor].width();
+        (line, column)
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Inserts pasted text. In a single-line input, line breaks become
+    /// spaces.
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let text = if self.multiline {
+            text
+        } else {
+            text.replace('\n', " ")
+        };
+        self.text.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+    }
+
+    /// Applies an editing key. Returns whether the key was used.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        // AltGr arrives as Ctrl+Alt on Windows and is used to type
+        // characters such as `@` or `{`, so only one of the two on its own
+        // counts as a modifier.
+        let ctrl_held = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt_held = key.modifiers.contains(KeyModifiers::ALT);
+        let ctrl = ctrl_held && !alt_held;
+        let alt = alt_held && !ctrl_held;
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_to(self.word_start()),
+            KeyCode::Char('u') if ctrl => self.delete_to(self.line_start()),
+            KeyCode::Char('k') if ctrl => self.delete_to(self.line_end()),
+            KeyCode::Char('a') if ctrl => self.cursor = self.line_start(),
+            KeyCode::Char('e') if ctrl => self.cursor = self.line_end(),
+            KeyCode::Char('b') if alt => self.cursor = self.word_start(),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end(),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert_char(c),
+            KeyCode::Backspace if ctrl || alt => self.delete_to(self.word_start()),
+            KeyCode::Backspace => self.delete_to(self.prev_boundary()),
+            KeyCode::Delete => self.delete_to(self.next_boundary()),
+            KeyCode::Left if ctrl || alt => self.cursor = self.word_start(),
+            KeyCode::Right if ctrl || alt => self.cursor = self.word_end(),
+            KeyCode::Left => self.cursor = self.prev_boundary(),
+            KeyCode::Right => self.cursor = self.next_boundary(),
+            KeyCode::Home => self.cursor = self.line_start(),
+            KeyCode::End => self.cursor = self.line_end(),
+            KeyCode::Enter if self.multiline => self.insert_char('\n'),
+            KeyCode::Up if self.multiline => self.move_vertically(-1),
+            KeyCode::Down if self.multiline => self.move_vertically(1),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Deletes the text between the cursor and `pos`, leaving the cursor
+    /// at the start of the deleted range.
+    fn delete_to(&mut self, pos: usize) {
+        let range = self.cursor.min(pos)..self.cursor.max(pos);
+        self.cursor = range.start;
+        self.text.replace_range(range, "");
+    }
+
+    /// Moves the cursor to the previous or next line, keeping its column as
+    /// far as the line allows.
+    fn move_vertically(&mut self, delta: i32) {
+        let (_, column) = self.cursor_position();
+        let start = self.line_start();
+        let end = self.line_end();
+        let (line_start, line_end) = if delta < 0 {
+            if start == 0 {
+                return;
+            }
+            (
+                self.text[..start - 1].rfind('\n').map_or(0, |i| i + 1),
+                start - 1,
+            )
+        } else {
+            if end == self.text.len() {
+                return;
+            }
+            let next = end + 1;
+            let next_end = self.text[next..]
+                .find('\n')
+                .map_or(self.text.len(), |i| next + i);
+            (next, next_end)
+        };
+
+        self.cursor = line_start;
+        for (i, g) in self.text[line_start..line_end].grapheme_indices(true) {
+            if self.text[line_start..line_start + i + g.len()].width() > column {
+                break;
+            }
+            self.cursor = line_start + i + g.len();
+        }
+    }
+
+    fn line_start(&self) -> usize {
+        self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i)
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    /// Start of the word before the cursor, skipping whitespace first.
+    fn word_start(&self) -> usize {
+        let mut pos = self.cursor;
+        let mut in_word = false;
+        for (i, g) in self.text[..self.cursor].grapheme_indices(true).rev() {
+            let space = g.trim().is_empty();
+            if in_word && space {
+                break;
+            }
+            in_word |= !space;
+            pos = i;
+        }
+        pos
+    }
+
+    /// End of the word after the cursor, skipping whitespace first.
+    fn word_end(&self) -> usize {
+        let mut pos = self.cursor;
+        let mut in_word = false;
+        for g in self.text[self.cursor..].graphemes(true) {
+            let space = g.trim().is_empty();
+            if in_word && space {
+                break;
+            }
+            in_word |= !space;
+            pos += g.len();
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut TextInput, code: KeyCode) -> bool {
+        input.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn press_with(input: &mut TextInput, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        input.handle_key(KeyEvent::new(code, modifiers))
+    }
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        for c in text.chars() {
+            press(&mut input, KeyCode::Char(c));
+        }
+        input
+    }
+
+    #[test]
+    fn typing_inserts_at_the_cursor() {
+        let mut input = typed("helo");
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Char('l'));
+        assert_eq!(input.text(), "hello");
+        assert_eq!(input.cursor, 4);
+    }
+
+    #[test]
+    fn moves_and_deletes_whole_graphemes() {
+        // "e" followed by a combining acute accent, and a family emoji made
+        // of several code points joined together.
+        let mut input = TextInput::default();
+        input.set("ae\u{301}👨‍👩‍👧");
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.text(), "ae\u{301}");
+        press(&mut input, KeyCode::Left);
+        assert_eq!(input.cursor, 1);
+        press(&mut input, KeyCode::Delete);
+        assert_eq!(input.text(), "a");
+        press(&mut input, KeyCode::Right);
+        press(&mut input, KeyCode::Right);
+        assert_eq!(input.cursor, 1);
+    }
+
+    #[test]
+    fn backspace_and_delete_at_the_ends_do_nothing() {
+        let mut input = typed("ab");
+        press(&mut input, KeyCode::Delete);
+        assert_eq!(input.text(), "ab");
+        press(&mut input, KeyCode::Home);
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.text(), "ab");
+        assert_eq!(input.cursor, 0);
+    }
+
+    #[test]
+    fn word_movement_skips_whitespace_first() {
+        let mut input = typed("one two  three");
+        press_with(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(input.cursor, 9);
+        press_with(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(input.cursor, 4);
+        press_with(&mut input, KeyCode::Char('b'), KeyModifiers::ALT);
+        assert_eq!(input.cursor, 0);
+        press_with(&mut input, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(input.cursor, 3);
+        press_with(&mut input, KeyCode::Char('f'), KeyModifiers::ALT);
+        assert_eq!(input.cursor, 7);
+    }
+
+    #[test]
+    fn deletes_words_and_line_parts() {
+        let mut input = typed("one two ");
+        press_with(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.text(), "one ");
+        press_with(&mut input, KeyCode::Backspace, KeyModifiers::ALT);
+        assert_eq!(input.text(), "");
+
+        let mut input = typed("before after");
+        for _ in 0.."after".len() {
+            press(&mut input, KeyCode::Left);
+        }
+        press_with(&mut input, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(input.text(), "before ");
+        press_with(&mut input, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press_with(&mut input, KeyCode::Char('e'), KeyModifiers::CONTROL);
+        press(&mut input, KeyCode::Left);
+        press_with(&mut input, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(input.text(), " ");
+    }
+
+    #[test]
+    fn unknown_shortcuts_are_not_typed() {
+        let mut input = TextInput::default();
+        assert!(!press_with(
+            &mut input,
+            KeyCode::Char('s'),
+            KeyModifiers::CONTROL
+        ));
+        assert!(!press_with(
+            &mut input,
+            KeyCode::Char('x'),
+            KeyModifiers::ALT
+        ));
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn altgr_characters_are_typed() {
+        // AltGr is reported as Ctrl+Alt on Windows.
+        let altgr = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        let mut input = TextInput::default();
+        for c in ['@', '{', 'e', '\\'] {
+            assert!(press_with(&mut input, KeyCode::Char(c), altgr));
+        }
+        assert_eq!(input.text(), "@{e\\");
+    }
+
+    #[test]
+    fn single_line_input_leaves_enter_and_arrows_alone() {
+        let mut input = typed("a");
+        assert!(!press(&mut input, KeyCode::Enter));
+        assert!(!press(&mut input, KeyCode::Up));
+        assert_eq!(input.text(), "a");
+    }
+
+    #[test]
+    fn pasted_line_breaks_become_spaces_in_a_single_line() {
+        let mut input = TextInput::default();
+        input.insert_str("a\r\nb\nc");
+        assert_eq!(input.text(), "a b c");
+
+        let mut input = TextInput::multiline("");
+        input.insert_str("a\r\nb\rc");
+        assert_eq!(input.text(), "a\nb\nc");
+    }
+
+    #[test]
+    fn multiline_moves_between_lines_keeping_the_column() {
+        let mut input = TextInput::multiline("abcdef");
+        press(&mut input, KeyCode::Enter);
+        for c in "日本".chars() {
+            press(&mut input, KeyCode::Char(c));
+        }
+        assert_eq!(input.cursor_position(), (1, 4));
+        press(&mut input, KeyCode::Up);
+        assert_eq!(input.cursor_position(), (0, 4));
+        press(&mut input, KeyCode::Right);
+        press(&mut input, KeyCode::Down);
+        // Column 5 falls inside the second wide character.
+        assert_eq!(input.cursor_position(), (1, 4));
+        press(&mut input, KeyCode::Down);
+        assert_eq!(input.cursor_position(), (1, 4));
+        press(&mut input, KeyCode::Home);
+        press(&mut input, KeyCode::Up);
+        press(&mut input, KeyCode::Up);
+        assert_eq!(input.cursor_position(), (0, 0));
+    }
+
+    #[test]
+    fn home_and_end_act_on_the_cursor_line() {
+        let mut input = TextInput::multiline("one\ntwo\nthree");
+        press(&mut input, KeyCode::Up);
+        press(&mut input, KeyCode::Home);
+        assert_eq!(input.cursor, 4);
+        press(&mut input, KeyCode::End);
+        assert_eq!(input.cursor, 7);
+    }
+}
@@ -0,0 +1,229 @@
+use super::{App, InputMode};
+use crate::todo::{Change, Todo};
+use ratatui::style::Color;
+use std::cmp::Reverse;
+
+/// A deletion waiting for the user to confirm it.
+#[derive(Clone, Copy)]
+pub enum Deletion {
+    /// Moving the selected or marked todos to the trash.
+    Trash,
+    /// Deleting the highlighted todo in the trash for good.
+    Purge,
+    /// Deleting everything in the trash for good.
+    EmptyTrash,
+}
+
+impl App {
+    /// Moves the selected or marked todos to the trash, after asking first
+    /// if `confirm_delete` is set.
+    pub fn request_delete(&mut self) {
+        if !self.targets().is_empty() {
+            self.request_deletion(Deletion::Trash);
+        }
+    }
+
+    /// Deletes the highlighted todo in the trash for good, or with `all`
+    /// everything in it, after asking first if `confirm_delete` is set.
+    pub fn request_purge(&mut self, all: bool) {
+        if !self.trashed_todos().is_empty() {
+            self.request_deletion(if all {
+                Deletion::EmptyTrash
+            } else {
+                Deletion::Purge
+            });
+        }
+    }
+
+    fn request_deletion(&mut self, deletion: Deletion) {
+        if self.settings.confirm_delete {
+            self.pending_deletion = Some(deletion);
+            self.input_mode = InputMode::ConfirmingDelete;
+        } else {
+            self.perform_deletion(deletion);
+        }
+    }
+
+    /// The question asked before the pending deletion.
+    pub fn deletion_prompt(&self) -> String {
+        match self.pending_deletion {
+            Some(Deletion::Trash) => format!("{}?", self.describe("Delete", &self.targets())),
+            Some(Deletion::Purge) => {
+                let ids: Vec<usize> = self
+                    .trashed_todos()
+                    .get(self.trash_cursor)
+                    .map(|todo| todo.id)
+                    .into_iter()
+                    .collect();
+                format!("{} for good?", self.describe("Delete", &ids))
+            }
+            Some(Deletion::EmptyTrash) => {
+                match self.todos.iter().filter(|t| t.is_deleted()).count() {
+                    1 => "Empty the trash, deleting 1 todo for good?".to_string(),
+                    count => format!("Empty the trash, deleting {} todos for good?", count),
+                }
+            }
+            None => String::new(),
+        }
+    }
+
+    pub fn confirm_deletion(&mut self) {
+        if let Some(deletion) = self.pending_deletion.take() {
+            self.input_mode = return_mode(deletion);
+            self.perform_deletion(deletion);
+        }
+    }
+
+    pub fn cancel_deletion(&mut self) {
+        if let Some(deletion) = self.pending_deletion.take() {
+            self.input_mode = return_mode(deletion);
+        }
+    }
+
+    fn perform_deletion(&mut self, deletion: Deletion) {
+        match deletion {
+            Deletion::Trash => self.delete_todo(),
+            Deletion::Purge => self.purge(false),
+            Deletion::EmptyTrash => self.purge(true),
+        }
+    }
+
+    /// The todos in the trash, most recently deleted first. Subtasks that
+    /// were trashed along with their parent are left out; they are
+    /// restored and purged together with it.
+    pub fn trashed_todos(&self) -> Vec<&Todo> {
+        let mut todos: Vec<&Todo> = self
+            .todos
+            .iter()
+            .filter(|todo| todo.is_deleted() && !self.trashed_with_parent(todo))
+            .collect();
+        todos.sort_by_key(|todo| Reverse(todo.deleted_at));
+        todos
+    }
+
+    /// Ids of the subtasks, at any depth, trashed along with the todo with
+    /// `id`.
+    pub fn trashed_subtasks(&self, id: usize) -> Vec<usize> {
+        let mut ids = Vec::new();
+        let mut parents = vec![id];
+        while let Some(parent) = parents.pop() {
+            for todo in &self.todos {
+                if todo.parent == Some(parent) && self.trashed_with_parent(todo) {
+                    ids.push(todo.id);
+                    parents.push(todo.id);
+                }
+            }
+        }
+        ids
+    }
+
+    fn trashed_with_parent(&self, todo: &Todo) -> bool {
+        todo.is_deleted()
+            && self.todos.iter().any(|parent| {
+                Some(parent.id) == todo.parent && parent.deleted_at == todo.deleted_at
+            })
+    }
+
+    pub fn open_trash(&mut self) {
+        if self.trashed_todos().is_empty() {
+            self.show_message("The trash is empty", Color::Yellow);
+            return;
+        }
+        self.trash_cursor = 0;
+        self.input_mode = InputMode::BrowsingTrash;
+    }
+
+    pub fn move_trash_cursor(&mut self, delta: i32) {
+        let len = self.trashed_todos().len();
+        let cursor = self.trash_cursor as i64 + i64::from(delta);
+        self.trash_cursor = cursor.clamp(0, len.saturating_sub(1) as i64) as usize;
+    }
+
+    /// Takes the highlighted todo and its subtasks out of the trash and
+    /// selects it. It goes back under its parent unless that is gone or in
+    /// the trash too.
+    pub fn restore_trashed(&mut self) {
+        let Some(todo) = self.trashed_todos().get(self.trash_cursor).copied() else {
+            return;
+        };
+        let (id, parent) = (todo.id, todo.parent);
+        let description = format!("restore \"{}\"", todo.description);
+        let parent = parent.filter(|parent| {
+            self.todos
+                .iter()
+                .any(|todo| todo.id == *parent && !todo.is_deleted())
+        });
+        let subtasks = self.trashed_subtasks(id);
+        let mut changes: Vec<Change> = self
+            .update_todo(id, |todo| {
+                todo.deleted_at = None;
+                todo.parent = parent;
+            })
+            .into_iter()
+            .collect();
+        for subtask in subtasks {
+            changes.extend(self.update_todo(subtask, |todo| todo.deleted_at = None));
+        }
+        if self.commit(description, changes) {
+            self.show_message("Todo restored", Color::Green);
+        }
+        self.selected_id = Some(id);
+        self.sync_selection(None);
+        self.leave_trash_if_empty();
+    }
+
+    /// Removes the highlighted todo with its subtasks, or with `all` every
+    /// todo, in the trash. Both can still be undone.
+    fn purge(&mut self, all: bool) {
+        let (description, ids) = if all {
+            let ids = self
+                .todos
+                .iter()
+                .filter(|todo| todo.is_deleted())
+                .map(|todo| todo.id)
+                .collect();
+            ("empty the trash".to_string(), ids)
+        } else {
+            let Some(id) = self
+                .trashed_todos()
+                .get(self.trash_cursor)
+                .map(|todo| todo.id)
+            else {
+                return;
+            };
+            let mut ids = vec![id];
+            ids.extend(self.trashed_subtasks(id));
+            (self.describe("purge", &[id]), ids)
+        };
+        if ids.is_empty() {
+            return;
+        }
+
+        let mut changes = Vec::new();
+        for id in ids {
+            if let Some(index) = self.todos.iter().position(|todo| todo.id == id) {
+                let todo = self.todos.remove(index);
+                changes.push(Change::Removed { index, todo });
+            }
+        }
+        if self.commit(description, changes) {
+            self.show_message("Deleted for good; press u to undo", Color::Green);
+        }
+        self.move_trash_cursor(0);
+        self.leave_trash_if_empty();
+    }
+
+    fn leave_trash_if_empty(&mut self) {
+        if self.trashed_todos().is_empty() && matches!(self.input_mode, InputMode::BrowsingTrash) {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+}
+
+/// The mode to go back to once a deletion is confirmed or cancelled.
+fn return_mode(deletion: Deletion) -> InputMode {
+    match deletion {
+        Deletion::Trash => InputMode::Normal,
+        Deletion::Purge | Deletion::EmptyTrash => InputMode::BrowsingTrash,
+    }
+}
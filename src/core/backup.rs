@@ -0,0 +1,94 @@
+use super::error::Result;
+use chrono::Local;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// How long to wait after a backup before taking another one on save, so
+/// that a burst of edits does not rotate out the backups taken before it.
+const BACKUP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Rotating, timestamped copies of a file, kept in a directory of their own.
+pub struct Backups {
+    dir: PathBuf,
+    keep: usize,
+    last_created: Option<Instant>,
+}
+
+impl Backups {
+    pub fn new(dir: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            keep,
+            last_created: None,
+        }
+    }
+
+    /// Copies `file` into the backup directory, then deletes the oldest
+    /// backups of it beyond the configured count. Does nothing if `file`
+    /// does not exist yet.
+    pub fn create(&mut self, file: &Path) -> Result<()> {
+        if self.keep == 0 || !file.exists() {
+            return Ok(());
+        }
+        self.last_created = Some(Instant::now());
+
+        fs::create_dir_all(&self.dir)?;
+        let name = format!(
+            "{}-{}.{}",
+            stem(file),
+            Local::now().format("%Y%m%d-%H%M%S-%6f"),
+            extension(file)
+        );
+        fs::copy(file, self.dir.join(name))?;
+
+        for old in self.list(file)?.into_iter().skip(self.keep) {
+            fs::remove_file(old)?;
+        }
+        Ok(())
+    }
+
+    /// Backs up `file` like `create`, unless a backup was already taken in
+    /// the last `BACKUP_INTERVAL`. The first save of a session is always
+    /// backed up.
+    pub fn create_if_due(&mut self, file: &Path) -> Result<()> {
+        match self.last_created {
+            Some(last) if last.elapsed() < BACKUP_INTERVAL => Ok(()),
+            _ => self.create(file),
+        }
+    }
+
+    /// Lists the backups of `file`, newest first.
+    pub fn list(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        let prefix = format!("{}-", stem(file));
+        let suffix = format!(".{}", extension(file));
+
+        let mut backups = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(&suffix))
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        // Timestamps are zero-padded, so names sort chronologically.
+        backups.sort();
+        backups.reverse();
+        Ok(backups)
+    }
+}
+
+fn stem(file: &Path) -> &str {
+    file.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("backup")
+}
+
+fn extension(file: &Path) -> &str {
+    file.extension().and_then(|s| s.to_str()).unwrap_or("bak")
+}
@@ -1,40 +1,101 @@
-use crate::todo::Todo;
+use super::error::{Error, Result};
+use super::storage::write_atomic;
+use crate::todo::{GroupBy, SortMode};
 use directories::ProjectDirs;
-use std::{
-    fs,
-    io::{self, Result},
-    path::PathBuf,
-};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
 
-pub fn load_todos() -> Result<Vec<Todo>> {
-    let config_dir = get_config_dir()?;
-    let todo_file = config_dir.join("todos.json");
+/// User settings, read from `config.json` in the config directory. Missing
+/// fields fall back to their defaults.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    /// Number of backups of `todos.json` to keep. Zero disables backups.
+    pub backup_count: usize,
+    /// Whether completing a todo also completes all of its subtasks.
+    pub cascade_completion: bool,
+    /// The order todos are listed in outside saved views.
+    pub sort: SortMode,
+    /// Whether completed todos are listed after open ones whatever the
+    /// sort order.
+    pub sink_completed: bool,
+    /// How the list is split into sections.
+    pub group_by: GroupBy,
+    /// Whether deleting todos asks for confirmation first.
+    pub confirm_delete: bool,
+}
 
-    match fs::read_to_string(todo_file) {
-        Ok(contents) => {
-            serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            backup_count: 10,
+            cascade_completion: true,
+            sort: SortMode::Manual,
+            sink_completed: false,
+            group_by: GroupBy::None,
+            confirm_delete: true,
         }
-        Err(_) => Ok(Vec::new()),
     }
 }
 
-pub fn save_todos(todos: &[Todo]) {
-    if let Ok(config_dir) = get_config_dir() {
-        let todo_file = config_dir.join("todos.json");
-        if let Ok(json) = serde_json::to_string_pretty(todos) {
-            let _ = fs::create_dir_all(&config_dir);
-            let _ = fs::write(todo_file, json);
+/// Loads the settings, falling back to the defaults if the file is missing
+/// or invalid.
+pub fn load_settings() -> Settings {
+    get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("config.json")).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Writes the settings to `config.json`. An existing file that is not
+/// valid settings is left alone rather than replaced by the defaults it was
+/// read as.
+pub fn save_settings(settings: &Settings) -> Result<()> {
+    let dir = get_config_dir()?;
+    let path = dir.join("config.json");
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            if serde_json::from_str::<Settings>(&contents).is_err() {
+                return Err(Error::Unreadable(path.display().to_string()));
+            }
         }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => fs::create_dir_all(&dir)?,
+        Err(e) => return Err(e.into()),
+    }
+    let json = serde_json::to_string_pretty(settings)?;
+    write_atomic(&path, json.as_bytes())
+}
+
+/// A named filter query with a sort order, shown as an extra filter tab.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct View {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub sort: SortMode,
+}
+
+/// Loads the saved views from `views.json` in the config directory. A
+/// missing file means there are no views yet.
+pub fn load_views() -> Result<Vec<View>> {
+    let path = get_config_dir()?.join("views.json");
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
     }
 }
 
-fn get_config_dir() -> Result<PathBuf> {
+pub fn save_views(views: &[View]) -> Result<()> {
+    let dir = get_config_dir()?;
+    fs::create_dir_all(&dir)?;
+    let json = serde_json::to_string_pretty(views)?;
+    write_atomic(&dir.join("views.json"), json.as_bytes())
+}
+
+pub fn get_config_dir() -> Result<PathBuf> {
     ProjectDirs::from("com", "pyyupsk", "todui")
         .map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
-        .ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::NotFound,
-                "Could not determine config directory",
-            )
-        })
+        .ok_or_else(|| Error::Config("Could not determine config directory".to_string()))
 }
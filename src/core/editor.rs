@@ -0,0 +1,101 @@
+use super::error::{Error, Result};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::{
+    env,
+    fs::{self, DirBuilder, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    process::{self, Command},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Lets the user edit `text` in `$VISUAL` or `$EDITOR` (falling back to
+/// `vi`, or `notepad` on Windows) and returns the edited text. The terminal
+/// must already be handed back to the user. A single trailing newline added
+/// by the editor is dropped.
+pub fn edit_text(text: &str, name: &str) -> Result<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    let file = TempFile::create(name, text)?;
+
+    // Editors are often configured with arguments, e.g. `code --wait`.
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(&file.path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&file.path).map_err(Error::from),
+        Ok(status) => Err(Error::Config(format!("{} exited with {}", editor, status))),
+        Err(e) => Err(Error::Config(format!("Could not run {}: {}", editor, e))),
+    };
+    drop(file);
+
+    let text = result?;
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+    Ok(text.strip_suffix('\r').unwrap_or(text).to_string())
+}
+
+/// A file readable only by the current user, in a directory of its own so
+/// that nobody else can create or replace it first. Both are removed when
+/// it is dropped.
+struct TempFile {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn create(name: &str, contents: &str) -> Result<TempFile> {
+        let dir = private_dir()?;
+        // From here on, dropping `file` cleans up the directory as well.
+        let file = TempFile {
+            path: dir.join(name),
+            dir,
+        };
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options.open(&file.path)?.write_all(contents.as_bytes())?;
+        Ok(file)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // Editors may leave swap or backup files next to the file.
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Creates a new directory in the system temp directory that only the
+/// current user can access. An existing directory is never reused.
+fn private_dir() -> Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.subsec_nanos());
+    for attempt in 0..100 {
+        let dir = env::temp_dir().join(format!("todui-{}-{}-{}", process::id(), nanos, attempt));
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        builder.mode(0o700);
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(Error::Config(
+        "Could not create a temporary directory".to_string(),
+    ))
+}
@@ -9,6 +9,13 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Refusing to overwrite {0}, which could not be loaded")]
+    Unreadable(String),
+
     #[error("Config error: {0}")]
     Config(String),
 }
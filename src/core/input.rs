@@ -1,47 +1,149 @@
-use crate::{App, InputMode};
-use crossterm::event::{Event, KeyCode};
-use std::mem;
+use crate::{app::TextInput, App, InputMode};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 
-pub fn handle_input(app: &mut App, key: Event) {
-    if let Event::Key(key) = key {
+pub fn handle_input(app: &mut App, event: Event) {
+    if let Event::Paste(text) = &event {
+        if !matches!(
+            app.input_mode,
+            InputMode::Normal
+                | InputMode::BrowsingTags
+                | InputMode::ConfirmingDelete
+                | InputMode::BrowsingTrash
+                | InputMode::Help
+        ) {
+            app.input.insert_str(text);
+            if matches!(app.input_mode, InputMode::Searching) {
+                app.update_search();
+            }
+        }
+    }
+
+    if let Event::Key(key) = event {
         match app.input_mode {
-            InputMode::Normal => handle_normal_mode(app, key.code),
-            InputMode::AddingTodo => handle_editing_mode(app, key.code),
-            InputMode::AddingTags => handle_adding_tags_mode(app, key.code),
-            InputMode::AddingNote => handle_adding_note_mode(app, key.code),
+            InputMode::Normal => handle_normal_mode(app, key),
+            InputMode::AddingTodo => handle_editing_mode(app, key),
+            InputMode::EditingTodo => handle_editing_todo_mode(app, key),
+            InputMode::AddingTags => handle_adding_tags_mode(app, key),
+            InputMode::AddingNote => handle_adding_note_mode(app, key),
+            InputMode::SettingDue => handle_setting_due_mode(app, key),
+            InputMode::SettingRecurrence => handle_setting_recurrence_mode(app, key),
+            InputMode::SettingBlockers => handle_setting_blockers_mode(app, key),
+            InputMode::TaggingMarked => handle_tagging_marked_mode(app, key),
+            InputMode::MovingTodos => handle_moving_todos_mode(app, key),
+            InputMode::Searching => handle_searching_mode(app, key),
+            InputMode::EnteringQuery => handle_entering_query_mode(app, key),
+            InputMode::NamingView => handle_naming_view_mode(app, key),
+            InputMode::BrowsingTags => handle_browsing_tags_mode(app, key.code),
+            InputMode::ConfirmingDelete => handle_confirming_delete_mode(app, key.code),
+            InputMode::BrowsingTrash => handle_browsing_trash_mode(app, key.code),
             InputMode::Help => handle_help_mode(app, key.code),
         }
     }
 }
 
-fn handle_normal_mode(app: &mut App, key: KeyCode) {
-    match key {
+fn handle_normal_mode(app: &mut App, key: KeyEvent) {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        if key.code == KeyCode::Char('r') {
+            app.redo();
+        }
+        return;
+    }
+
+    match key.code {
         KeyCode::Char('a') => {
             app.input_mode = InputMode::AddingTodo;
             app.input.clear();
         }
+        KeyCode::Char('e') => {
+            if let Some(todo) = app.get_selected_todo() {
+                app.input.set(todo.description.clone());
+                app.input_mode = InputMode::EditingTodo;
+            }
+        }
         KeyCode::Char('j') | KeyCode::Down => app.move_selection(1),
         KeyCode::Char('k') | KeyCode::Up => app.move_selection(-1),
+        KeyCode::Char('J') => app.move_todo(true),
+        KeyCode::Char('K') => app.move_todo(false),
         KeyCode::Char(' ') => app.toggle_todo(),
-        KeyCode::Char('d') => app.delete_todo(),
+        KeyCode::Char('d') => app.request_delete(),
         KeyCode::Char('p') => app.toggle_priority(),
+        KeyCode::Char('u') => app.undo(),
+        KeyCode::Char('t') if !app.marked_ids().is_empty() => {
+            app.input.clear();
+            app.input_mode = InputMode::TaggingMarked;
+        }
         KeyCode::Char('t') => {
-            if app.selected_index.is_some() {
+            if let Some(todo) = app.get_selected_todo() {
+                app.input.set(todo.tags.join(", "));
                 app.input_mode = InputMode::AddingTags;
-                if let Some(todo) = app.get_selected_todo() {
-                    app.input = todo.tags.join(", ");
-                }
             }
         }
-        KeyCode::Char('n') => {
-            if app.selected_index.is_some() {
+        KeyCode::Char('o') => {
+            if let Some(todo) = app.get_selected_todo() {
+                app.input = TextInput::multiline(todo.notes.clone());
                 app.input_mode = InputMode::AddingNote;
-                if let Some(todo) = app.get_selected_todo() {
-                    app.input = todo.notes.clone();
-                }
             }
         }
+        KeyCode::Char('E') => app.request_editor(),
+        KeyCode::Char('/') => app.start_search(),
+        KeyCode::Char('n') => app.jump_to_match(true),
+        KeyCode::Char('N') => app.jump_to_match(false),
+        KeyCode::Char('x') => app.toggle_mark(),
+        KeyCode::Char('v') => app.toggle_range_mark(),
+        KeyCode::Char('m') if !app.marked_ids().is_empty() || app.get_selected_todo().is_some() => {
+            app.input.clear();
+            app.input_mode = InputMode::MovingTodos;
+        }
+        KeyCode::Esc => app.clear_marks_or_search(),
+        KeyCode::Char('i') => app.toggle_details(),
+        KeyCode::PageDown => app.scroll_details(5),
+        KeyCode::PageUp => app.scroll_details(-5),
+        KeyCode::Char('D') => {
+            if let Some(todo) = app.get_selected_todo() {
+                app.input.set(todo.due_label().unwrap_or_default());
+                app.input_mode = InputMode::SettingDue;
+            }
+        }
+        KeyCode::Char('r') => {
+            if let Some(todo) = app.get_selected_todo() {
+                app.input.set(
+                    todo.recurrence
+                        .as_ref()
+                        .map(|recurrence| recurrence.label())
+                        .unwrap_or_default(),
+                );
+                app.input_mode = InputMode::SettingRecurrence;
+            }
+        }
+        KeyCode::Char('b') => {
+            if let Some(todo) = app.get_selected_todo() {
+                app.input.set(
+                    todo.blocked_by
+                        .iter()
+                        .map(|id| format!("#{}", id))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                );
+                app.input_mode = InputMode::SettingBlockers;
+            }
+        }
+        KeyCode::Char('>') => app.indent_todo(),
+        KeyCode::Char('<') => app.outdent_todo(),
+        KeyCode::Char('z') => app.toggle_collapsed(),
         KeyCode::Tab => app.cycle_filter(),
+        KeyCode::Char('s') => app.cycle_sort(),
+        KeyCode::Char('g') => app.cycle_group(),
+        KeyCode::Char('f') => {
+            app.input.set(app.query_source());
+            app.input_mode = InputMode::EnteringQuery;
+        }
+        KeyCode::Char('V') => {
+            app.input.set(app.view_name());
+            app.input_mode = InputMode::NamingView;
+        }
+        KeyCode::Char('X') => app.delete_view(),
+        KeyCode::Char('#') => app.open_tag_browser(),
+        KeyCode::Char('T') => app.open_trash(),
         KeyCode::Char('?') => {
             app.input_mode = if matches!(app.input_mode, InputMode::Help) {
                 InputMode::Normal
@@ -53,67 +155,244 @@ fn handle_normal_mode(app: &mut App, key: KeyCode) {
     }
 }
 
-fn handle_editing_mode(app: &mut App, key: KeyCode) {
-    match key {
+fn handle_editing_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
         KeyCode::Enter => {
-            let input = mem::take(&mut app.input);
+            let input = app.input.take();
             if !input.is_empty() {
                 app.add_todo(input);
                 app.input_mode = InputMode::Normal;
             }
         }
-        KeyCode::Char(c) => {
-            app.input.push(c);
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
         }
-        KeyCode::Backspace => {
-            app.input.pop();
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_editing_todo_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            let input = app.input.take();
+            if !input.is_empty() {
+                app.edit_todo(input);
+                app.input_mode = InputMode::Normal;
+            }
         }
         KeyCode::Esc => {
             app.input_mode = InputMode::Normal;
             app.input.clear();
         }
-        _ => {}
+        _ => {
+            app.input.handle_key(key);
+        }
     }
 }
 
-fn handle_adding_tags_mode(app: &mut App, key: KeyCode) {
-    match key {
+fn handle_adding_tags_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Tab => app.complete_tag(),
         KeyCode::Enter => {
-            let tags = mem::take(&mut app.input);
+            let tags = app.input.take();
             app.add_tags(tags);
             app.input_mode = InputMode::Normal;
         }
-        KeyCode::Char(c) => {
-            app.input.push(c);
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
         }
-        KeyCode::Backspace => {
-            app.input.pop();
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_tagging_marked_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Tab => app.complete_tag(),
+        KeyCode::Enter => {
+            let tags = app.input.take();
+            app.tag_marked(tags);
+            app.input_mode = InputMode::Normal;
         }
         KeyCode::Esc => {
             app.input_mode = InputMode::Normal;
             app.input.clear();
         }
-        _ => {}
+        _ => {
+            app.input.handle_key(key);
+        }
     }
 }
 
-fn handle_adding_note_mode(app: &mut App, key: KeyCode) {
-    match key {
+fn handle_moving_todos_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
         KeyCode::Enter => {
-            let note = mem::take(&mut app.input);
+            if app.move_todos(app.input.text().to_string()) {
+                app.input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
+        }
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_adding_note_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            let note = app.input.take();
             app.add_note(note);
             app.input_mode = InputMode::Normal;
         }
-        KeyCode::Char(c) => {
-            app.input.push(c);
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
         }
-        KeyCode::Backspace => {
-            app.input.pop();
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_setting_due_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            if app.set_due(app.input.text().to_string()) {
+                app.input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
+        }
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_setting_recurrence_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            if app.set_recurrence(app.input.text().to_string()) {
+                app.input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
+        }
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_setting_blockers_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            if app.set_blockers(app.input.text().to_string()) {
+                app.input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
+        }
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_entering_query_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            if app.apply_query(app.input.text().to_string()) {
+                app.input.clear();
+                app.input_mode = InputMode::Normal;
+            }
         }
         KeyCode::Esc => {
             app.input_mode = InputMode::Normal;
             app.input.clear();
         }
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_naming_view_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            if app.save_view(app.input.text().to_string()) {
+                app.input.clear();
+                app.input_mode = InputMode::Normal;
+            }
+        }
+        KeyCode::Esc => {
+            app.input_mode = InputMode::Normal;
+            app.input.clear();
+        }
+        _ => {
+            app.input.handle_key(key);
+        }
+    }
+}
+
+fn handle_searching_mode(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => app.finish_search(true),
+        KeyCode::Esc => app.finish_search(false),
+        _ => {
+            app.input.handle_key(key);
+            app.update_search();
+        }
+    }
+}
+
+fn handle_browsing_tags_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => app.move_tag_cursor(1),
+        KeyCode::Char('k') | KeyCode::Up => app.move_tag_cursor(-1),
+        KeyCode::Char(' ') => app.toggle_tag_mark(),
+        KeyCode::Char('m') => app.tag_browser.match_all = !app.tag_browser.match_all,
+        KeyCode::Enter => app.apply_tag_filter(),
+        KeyCode::Esc => app.input_mode = InputMode::Normal,
+        _ => {}
+    }
+}
+
+fn handle_confirming_delete_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.confirm_deletion(),
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_deletion(),
+        _ => {}
+    }
+}
+
+fn handle_browsing_trash_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => app.move_trash_cursor(1),
+        KeyCode::Char('k') | KeyCode::Up => app.move_trash_cursor(-1),
+        KeyCode::Char('r') => app.restore_trashed(),
+        KeyCode::Char('d') => app.request_purge(false),
+        KeyCode::Char('D') => app.request_purge(true),
+        KeyCode::Esc => app.input_mode = InputMode::Normal,
         _ => {}
     }
 }
@@ -1,6 +1,9 @@
+pub mod backup;
 pub mod config;
+pub mod editor;
 pub mod error;
 pub mod input;
+pub mod storage;
 
-pub use config::{load_todos, save_todos};
 pub use error::{Error, Result};
+pub use storage::{JsonStorage, MemoryStorage, Storage};
@@ -0,0 +1,455 @@
+use super::Storage;
+use crate::core::{
+    backup::Backups,
+    config::{get_config_dir, load_settings},
+    error::{Error, Result},
+};
+use crate::todo::{History, HistoryStep, Todo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Version of the file format written by this build.
+///
+/// Raise it, with a migration, whenever older builds would lose meaning by
+/// dropping a new field on save, so that they refuse the file instead.
+const CURRENT_VERSION: u64 = 3;
+
+/// Upgrades a file from version `i` to version `i + 1`.
+const MIGRATIONS: [fn(Value) -> Value; CURRENT_VERSION as usize] = [
+    // Version 0 was a bare list of todos.
+    |todos| json!({ "next_id": 1, "todos": todos }),
+    // Version 1 added the id counter but carried no version field.
+    |mut file| {
+        file["version"] = json!(2);
+        file
+    },
+    // Version 3 added trashed todos, which older builds would bring back.
+    // Existing files have none, so only the version changes.
+    |mut file| {
+        file["version"] = json!(3);
+        file
+    },
+];
+
+#[derive(Serialize)]
+struct TodoFile<'a> {
+    version: u64,
+    next_id: usize,
+    todos: &'a [Todo],
+}
+
+#[derive(Deserialize)]
+struct StoredFile {
+    next_id: usize,
+    todos: Vec<Todo>,
+}
+
+/// Stores todos in a pretty-printed JSON file, by default `todos.json` in the
+/// user's config directory.
+///
+/// The whole file is rewritten on every change; per-todo updates are applied
+/// to a cached copy of the list first. If backups are configured, the
+/// previous file is copied aside before the first write of a session, and
+/// again before later writes once enough time has passed.
+pub struct JsonStorage {
+    path: PathBuf,
+    backups: Option<Backups>,
+    todos: Vec<Todo>,
+    next_id: usize,
+    // Set when the file exists but could not be loaded, so that it is never
+    // replaced by whatever the app ends up with instead.
+    unreadable: bool,
+}
+
+impl JsonStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            backups: None,
+            todos: Vec::new(),
+            next_id: 1,
+            unreadable: false,
+        }
+    }
+
+    pub fn with_backups(mut self, backups: Backups) -> Self {
+        self.backups = Some(backups);
+        self
+    }
+
+    /// Storage backed by `todos.json` in the user's config directory, with
+    /// backups kept in its `backups` subdirectory.
+    pub fn open_default() -> Result<Self> {
+        let config_dir = get_config_dir()?;
+        let backups = Backups::new(config_dir.join("backups"), load_settings().backup_count);
+        Ok(Self::new(config_dir.join("todos.json")).with_backups(backups))
+    }
+
+    /// Reads the todos from the file at `path`, migrated and with ids
+    /// repaired as by `load`, without writing anything back.
+    pub fn read(path: &Path) -> Result<(Vec<Todo>, usize)> {
+        let StoredFile { mut todos, next_id } = parse(&fs::read_to_string(path)?)?;
+        let (next_id, _) = repair_ids(&mut todos, next_id);
+        Ok((todos, next_id))
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.path.with_extension("history.json")
+    }
+
+    /// Lists the available backups, newest first.
+    pub fn backups(&self) -> Result<Vec<PathBuf>> {
+        match &self.backups {
+            Some(backups) => backups.list(&self.path),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replaces the file with `backup` and returns the number of todos it
+    /// holds. The backup is checked to load first, and the file it replaces
+    /// is itself backed up. The undo history is deleted, as its edits do
+    /// not apply to the restored todos.
+    pub fn restore(&mut self, backup: &Path) -> Result<usize> {
+        let contents = fs::read_to_string(backup)?;
+        let stored = parse(&contents)?;
+        if let Some(backups) = &mut self.backups {
+            backups.create(&self.path)?;
+        }
+        write_atomic(&self.path, contents.as_bytes())?;
+        match fs::remove_file(self.history_path()) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        Ok(stored.todos.len())
+    }
+
+    /// Writes the cached todos. The file is replaced atomically, so a crash
+    /// or a full disk leaves the previous contents intact.
+    fn write(&mut self) -> Result<()> {
+        if self.unreadable {
+            return Err(Error::Unreadable(self.path.display().to_string()));
+        }
+
+        let json = serde_json::to_string_pretty(&TodoFile {
+            version: CURRENT_VERSION,
+            next_id: self.next_id,
+            todos: &self.todos,
+        })?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if let Some(backups) = &mut self.backups {
+            backups.create_if_due(&self.path)?;
+        }
+        write_atomic(&self.path, json.as_bytes())
+    }
+}
+
+impl Storage for JsonStorage {
+    /// Files written by older versions are migrated, and duplicate ids left
+    /// behind by them are reassigned. The repaired list is written back if
+    /// possible, and otherwise with the next save.
+    /// If the file cannot be loaded, all further writes are refused.
+    fn load(&mut self) -> Result<(Vec<Todo>, usize)> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((Vec::new(), 1)),
+            Err(e) => {
+                self.unreadable = true;
+                return Err(e.into());
+            }
+        };
+
+        let StoredFile { mut todos, next_id } = parse(&contents).inspect_err(|_| {
+            self.unreadable = true;
+        })?;
+
+        let (next_id, repaired) = repair_ids(&mut todos, next_id);
+        self.todos = todos;
+        self.next_id = next_id;
+        // Failing here would leave the app with an empty list whose new
+        // todos reuse the ids of the ones on disk, so a failed write is not
+        // an error.
+        if repaired > 0 {
+            let _ = self.write();
+        }
+        Ok((self.todos.clone(), self.next_id))
+    }
+
+    fn save(&mut self, todos: &[Todo], next_id: usize) -> Result<()> {
+        self.todos = todos.to_vec();
+        self.next_id = next_id;
+        self.write()
+    }
+
+    fn upsert(&mut self, todo: &Todo) -> Result<()> {
+        match self.todos.iter_mut().find(|t| t.id == todo.id) {
+            Some(stored) => *stored = todo.clone(),
+            None => self.todos.push(todo.clone()),
+        }
+        self.next_id = self.next_id.max(todo.id + 1);
+        self.write()
+    }
+
+    fn delete(&mut self, id: usize) -> Result<()> {
+        self.todos.retain(|todo| todo.id != id);
+        self.write()
+    }
+
+    /// The history lives in a file next to the todos, e.g.
+    /// `todos.history.json`. An unreadable history is discarded.
+    fn load_history(&mut self) -> Result<History> {
+        Ok(fs::read_to_string(self.history_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default())
+    }
+
+    /// The whole history is rewritten, like the todos themselves.
+    fn save_history(&mut self, history: &History, _step: HistoryStep) -> Result<()> {
+        if self.unreadable {
+            return Err(Error::Unreadable(self.path.display().to_string()));
+        }
+        write_atomic(
+            &self.history_path(),
+            serde_json::to_string(history)?.as_bytes(),
+        )
+    }
+}
+
+fn parse(contents: &str) -> Result<StoredFile> {
+    let mut file: Value = serde_json::from_str(contents)?;
+    loop {
+        let version = match &file {
+            Value::Array(_) => 0,
+            Value::Object(fields) => fields.get("version").and_then(Value::as_u64).unwrap_or(1),
+            _ => return Err(Error::Config("todos.json is not a todo list".to_string())),
+        };
+
+        match MIGRATIONS.get(version as usize) {
+            Some(migrate) => file = migrate(file),
+            None if version == CURRENT_VERSION => return Ok(serde_json::from_value(file)?),
+            None => {
+                return Err(Error::Config(format!(
+                    "todos.json has version {}, but only up to {} is supported",
+                    version, CURRENT_VERSION
+                )))
+            }
+        }
+    }
+}
+
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    let result = (|| -> io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        // Make the rename itself durable.
+        #[cfg(unix)]
+        if let Some(dir) = path.parent() {
+            File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    Ok(result?)
+}
+
+/// Gives every todo whose id was already seen a fresh one and returns the
+/// next free id along with the number of todos that were renumbered.
+fn repair_ids(todos: &mut [Todo], next_id: usize) -> (usize, usize) {
+    let max_id = todos.iter().map(|todo| todo.id).max().unwrap_or(0);
+    let mut next_id = next_id.max(max_id + 1);
+    let mut seen = HashSet::new();
+    let mut repaired = 0;
+
+    for todo in todos.iter_mut() {
+        if !seen.insert(todo.id) {
+            todo.id = next_id;
+            seen.insert(next_id);
+            next_id += 1;
+            repaired += 1;
+        }
+    }
+
+    (next_id, repaired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::test_util::todo;
+
+    /// A fresh directory under the system temp directory, removed when
+    /// dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir =
+                std::env::temp_dir().join(format!("todui-test-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_file(path: &Path, todos: &[Todo], next_id: usize) {
+        let file = TodoFile {
+            version: CURRENT_VERSION,
+            next_id,
+            todos,
+        };
+        fs::write(path, serde_json::to_string(&file).unwrap()).unwrap();
+    }
+
+    fn ids(todos: &[Todo]) -> Vec<usize> {
+        todos.iter().map(|todo| todo.id).collect()
+    }
+
+    #[test]
+    fn repair_ids_renumbers_duplicates_only() {
+        let mut todos = vec![todo(1, "a"), todo(1, "b"), todo(4, "c"), todo(4, "d")];
+        assert_eq!(repair_ids(&mut todos, 2), (7, 2));
+        assert_eq!(ids(&todos), [1, 5, 4, 6]);
+    }
+
+    #[test]
+    fn repair_ids_keeps_next_id_past_the_largest_id() {
+        let mut todos = vec![todo(3, "a")];
+        assert_eq!(repair_ids(&mut todos, 1), (4, 0));
+        assert_eq!(repair_ids(&mut todos, 10), (10, 0));
+        assert_eq!(repair_ids(&mut [], 1), (1, 0));
+    }
+
+    #[test]
+    fn parse_migrates_every_older_version() {
+        let todos = serde_json::to_value([todo(1, "a")]).unwrap();
+        let files = [
+            todos.clone(),
+            json!({ "next_id": 2, "todos": todos }),
+            json!({ "version": 2, "next_id": 2, "todos": todos }),
+            json!({ "version": 3, "next_id": 2, "todos": todos }),
+        ];
+        for file in files {
+            let stored = parse(&file.to_string()).unwrap();
+            assert_eq!(ids(&stored.todos), [1]);
+            assert_eq!(stored.todos[0].description, "a");
+        }
+        // Version 0 had no counter, so it starts over and is repaired later.
+        assert_eq!(parse("[]").unwrap().next_id, 1);
+    }
+
+    #[test]
+    fn parse_refuses_newer_versions_and_non_lists() {
+        let newer = json!({ "version": CURRENT_VERSION + 1, "next_id": 1, "todos": [] });
+        assert!(matches!(parse(&newer.to_string()), Err(Error::Config(_))));
+        assert!(matches!(parse("42"), Err(Error::Config(_))));
+        assert!(parse("{ not json").is_err());
+    }
+
+    #[test]
+    fn unreadable_files_are_never_overwritten() {
+        let dir = TempDir::new("unreadable");
+        let path = dir.0.join("todos.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        let mut storage = JsonStorage::new(&path);
+        assert!(storage.load().is_err());
+        assert!(matches!(
+            storage.save(&[todo(1, "a")], 2),
+            Err(Error::Unreadable(_))
+        ));
+        assert!(matches!(
+            storage.upsert(&todo(1, "a")),
+            Err(Error::Unreadable(_))
+        ));
+        assert!(matches!(
+            storage.save_history(&History::default(), HistoryStep::Recorded),
+            Err(Error::Unreadable(_))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn repaired_ids_are_written_back() {
+        let dir = TempDir::new("repair");
+        let path = dir.0.join("todos.json");
+        write_file(&path, &[todo(1, "first"), todo(1, "second")], 2);
+
+        let (todos, next_id) = JsonStorage::new(&path).load().unwrap();
+        assert_eq!((ids(&todos), next_id), (vec![1, 2], 3));
+        let stored = parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(ids(&stored.todos), [1, 2]);
+    }
+
+    #[test]
+    fn read_repairs_ids_without_writing_back() {
+        let dir = TempDir::new("read");
+        let path = dir.0.join("todos.json");
+        write_file(&path, &[todo(1, "first"), todo(1, "second")], 2);
+        let contents = fs::read_to_string(&path).unwrap();
+
+        let (todos, next_id) = JsonStorage::read(&path).unwrap();
+        assert_eq!((ids(&todos), next_id), (vec![1, 2], 3));
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn repaired_ids_are_kept_when_writing_back_fails() {
+        let dir = TempDir::new("repair-fails");
+        let path = dir.0.join("todos.json");
+        write_file(&path, &[todo(1, "first"), todo(1, "second")], 2);
+        // A file where the backup directory should be makes backups fail.
+        fs::write(dir.0.join("backups"), "").unwrap();
+
+        let mut storage =
+            JsonStorage::new(&path).with_backups(Backups::new(dir.0.join("backups"), 5));
+        let (todos, next_id) = storage.load().unwrap();
+        assert_eq!((ids(&todos), next_id), (vec![1, 2], 3));
+
+        // The file on disk still holds both todos, under their old ids.
+        let stored = parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored.todos.len(), 2);
+    }
+
+    #[test]
+    fn restore_replaces_the_file_and_drops_the_history() {
+        let dir = TempDir::new("restore");
+        let path = dir.0.join("todos.json");
+        write_file(&path, &[todo(1, "a")], 2);
+        let mut storage =
+            JsonStorage::new(&path).with_backups(Backups::new(dir.0.join("backups"), 5));
+        storage.load().unwrap();
+        storage
+            .save(&[todo(1, "a-edited"), todo(2, "b")], 3)
+            .unwrap();
+        storage
+            .save_history(&History::default(), HistoryStep::Recorded)
+            .unwrap();
+        assert!(storage.history_path().exists());
+
+        let backup = storage.backups().unwrap().pop().unwrap();
+        assert_eq!(storage.restore(&backup).unwrap(), 1);
+        let (todos, _) = JsonStorage::new(&path).load().unwrap();
+        assert_eq!(todos[0].description, "a");
+        assert!(!storage.history_path().exists());
+    }
+}
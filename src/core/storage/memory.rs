@@ -0,0 +1,62 @@
+use super::Storage;
+use crate::core::error::Result;
+use crate::todo::{History, HistoryStep, Todo};
+
+/// Keeps todos in memory only. Useful for tests and for embedding todui
+/// without touching the filesystem.
+#[derive(Default)]
+pub struct MemoryStorage {
+    todos: Vec<Todo>,
+    next_id: usize,
+    history: History,
+}
+
+impl MemoryStorage {
+    pub fn new(todos: Vec<Todo>) -> Self {
+        let next_id = todos.iter().map(|todo| todo.id + 1).max().unwrap_or(1);
+        Self {
+            todos,
+            next_id,
+            history: History::default(),
+        }
+    }
+
+    pub fn todos(&self) -> &[Todo] {
+        &self.todos
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&mut self) -> Result<(Vec<Todo>, usize)> {
+        Ok((self.todos.clone(), self.next_id.max(1)))
+    }
+
+    fn save(&mut self, todos: &[Todo], next_id: usize) -> Result<()> {
+        self.todos = todos.to_vec();
+        self.next_id = next_id;
+        Ok(())
+    }
+
+    fn upsert(&mut self, todo: &Todo) -> Result<()> {
+        match self.todos.iter_mut().find(|t| t.id == todo.id) {
+            Some(stored) => *stored = todo.clone(),
+            None => self.todos.push(todo.clone()),
+        }
+        self.next_id = self.next_id.max(todo.id + 1);
+        Ok(())
+    }
+
+    fn delete(&mut self, id: usize) -> Result<()> {
+        self.todos.retain(|todo| todo.id != id);
+        Ok(())
+    }
+
+    fn load_history(&mut self) -> Result<History> {
+        Ok(self.history.clone())
+    }
+
+    fn save_history(&mut self, history: &History, _step: HistoryStep) -> Result<()> {
+        self.history = history.clone();
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+mod json;
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub(crate) use json::write_atomic;
+pub use json::JsonStorage;
+pub use memory::MemoryStorage;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+
+use super::error::Result;
+use crate::todo::{History, HistoryStep, Todo};
+
+/// A place todos are persisted to.
+///
+/// Besides replacing the whole list, backends support per-todo updates so
+/// that single edits do not have to rewrite everything. Backends keep track
+/// of the next free id themselves: it never drops below one past the largest
+/// id they have stored.
+pub trait Storage {
+    /// Loads every todo along with the next free id.
+    fn load(&mut self) -> Result<(Vec<Todo>, usize)>;
+
+    /// Replaces the stored todos with `todos`.
+    fn save(&mut self, todos: &[Todo], next_id: usize) -> Result<()>;
+
+    /// Inserts `todo`, or replaces the stored todo with the same id.
+    fn upsert(&mut self, todo: &Todo) -> Result<()>;
+
+    /// Removes the todo with the given id.
+    fn delete(&mut self, id: usize) -> Result<()>;
+
+    /// Loads the undo history. Backends that do not persist it start with
+    /// an empty one.
+    fn load_history(&mut self) -> Result<History> {
+        Ok(History::default())
+    }
+
+    /// Persists `history` after it went through `step`. Backends may use
+    /// `step` to write only the edits that moved.
+    fn save_history(&mut self, _history: &History, _step: HistoryStep) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Opens the storage used by `App::new`: a SQLite database when built with
+/// the `sqlite` feature, `todos.json` otherwise.
+pub fn open_default() -> Result<Box<dyn Storage>> {
+    #[cfg(feature = "sqlite")]
+    return Ok(Box::new(SqliteStorage::open_default()?));
+
+    #[cfg(not(feature = "sqlite"))]
+    return Ok(Box::new(JsonStorage::open_default()?));
+}
@@ -0,0 +1,294 @@
+use super::{JsonStorage, Storage};
+use crate::core::{
+    config::get_config_dir,
+    error::{Error, Result},
+};
+use crate::todo::{History, HistoryStep, Todo};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+/// Version of the database schema written by this build, kept in
+/// `PRAGMA user_version`. Version 2 added trashed todos, which older builds
+/// would restore on save, and moved the undo history from a single `meta`
+/// row into the `history` table.
+const SCHEMA_VERSION: u32 = 2;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS todos (
+        id   INTEGER PRIMARY KEY,
+        seq  INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key   TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS history (
+        id    INTEGER PRIMARY KEY,
+        stack TEXT NOT NULL,
+        data  TEXT NOT NULL
+    );
+";
+
+/// Stores todos in a SQLite database, by default `todos.db` in the user's
+/// config directory.
+///
+/// Each todo is a row holding its JSON representation, so per-todo updates
+/// only touch that row. `seq` keeps the order of the list. Likewise, each
+/// edit in the undo history is a row, on the `undo` or `redo` stack in the
+/// order of `id`.
+pub struct SqliteStorage {
+    conn: Connection,
+    // Set when stored todos could not be loaded; see `JsonStorage`.
+    unreadable: bool,
+}
+
+impl SqliteStorage {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(Error::Config(format!(
+                "todos.db has schema version {}, but only up to {} is supported",
+                version, SCHEMA_VERSION
+            )));
+        }
+
+        conn.execute_batch(SCHEMA)?;
+        // Version 1 kept the history as one row; it is simply dropped.
+        conn.execute("DELETE FROM meta WHERE key = 'history'", [])?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(Self {
+            conn,
+            unreadable: false,
+        })
+    }
+
+    /// Storage backed by `todos.db` in the user's config directory. The
+    /// first time the database is opened, todos from an existing
+    /// `todos.json` next to it are imported.
+    pub fn open_default() -> Result<Self> {
+        let config_dir = get_config_dir()?;
+        std::fs::create_dir_all(&config_dir)?;
+        let mut storage = Self::open(config_dir.join("todos.db"))?;
+        storage.import_json(config_dir.join("todos.json"))?;
+        Ok(storage)
+    }
+
+    /// Imports the todos from a JSON file written by [`JsonStorage`], unless
+    /// an import has already happened. The JSON file is left untouched.
+    pub fn import_json(&mut self, path: PathBuf) -> Result<()> {
+        if self.get_meta("json_imported")?.is_some() {
+            return Ok(());
+        }
+
+        if path.exists() {
+            let (todos, next_id) = JsonStorage::read(&path)?;
+            self.save(&todos, next_id)?;
+        }
+        self.set_meta("json_imported", "1")
+    }
+
+    fn get_meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [key, value],
+        )?;
+        Ok(())
+    }
+
+    fn check_writable(&self) -> Result<()> {
+        if self.unreadable {
+            return Err(Error::Unreadable("todos.db".to_string()));
+        }
+        Ok(())
+    }
+
+    fn next_id(&self) -> Result<usize> {
+        Ok(self
+            .get_meta("next_id")?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(1))
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&mut self) -> Result<(Vec<Todo>, usize)> {
+        let mut stmt = self.conn.prepare("SELECT data FROM todos ORDER BY seq")?;
+        let todos = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|data| Ok(serde_json::from_str(&data?)?))
+            .collect::<Result<Vec<Todo>>>()
+            .inspect_err(|_| self.unreadable = true)?;
+
+        let max_id = todos.iter().map(|todo| todo.id).max().unwrap_or(0);
+        Ok((todos, self.next_id()?.max(max_id + 1)))
+    }
+
+    fn save(&mut self, todos: &[Todo], next_id: usize) -> Result<()> {
+        self.check_writable()?;
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM todos", [])?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO todos (id, seq, data) VALUES (?1, ?2, ?3)")?;
+            for (seq, todo) in todos.iter().enumerate() {
+                stmt.execute(params![todo.id, seq, serde_json::to_string(todo)?])?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES ('next_id', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            [next_id.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn upsert(&mut self, todo: &Todo) -> Result<()> {
+        self.check_writable()?;
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO todos (id, seq, data)
+             VALUES (?1, (SELECT COALESCE(MAX(seq), -1) + 1 FROM todos), ?2)
+             ON CONFLICT(id) DO UPDATE SET data = excluded.data",
+            params![todo.id, serde_json::to_string(todo)?],
+        )?;
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES ('next_id', ?1)
+             ON CONFLICT(key) DO UPDATE SET
+                 value = MAX(CAST(value AS INTEGER), CAST(excluded.value AS INTEGER))",
+            [(todo.id + 1).to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, id: usize) -> Result<()> {
+        self.check_writable()?;
+        self.conn
+            .execute("DELETE FROM todos WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// An unreadable history is discarded.
+    fn load_history(&mut self) -> Result<History> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT stack, data FROM history ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let (mut undo, mut redo) = (Vec::new(), Vec::new());
+        for (stack, data) in rows {
+            let Ok(edit) = serde_json::from_str(&data) else {
+                return Ok(History::default());
+            };
+            match stack.as_str() {
+                "redo" => redo.push(edit),
+                _ => undo.push(edit),
+            }
+        }
+        Ok(History::from_stacks(undo, redo))
+    }
+
+    /// Only the edit that moved is written, rather than the whole history.
+    fn save_history(&mut self, history: &History, step: HistoryStep) -> Result<()> {
+        self.check_writable()?;
+        let tx = self.conn.transaction()?;
+        let (from, to, edit) = match step {
+            HistoryStep::Recorded => {
+                tx.execute("DELETE FROM history WHERE stack = 'redo'", [])?;
+                (None, "undo", history.undo_stack().last())
+            }
+            HistoryStep::Undone => (Some("undo"), "redo", history.redo_stack().last()),
+            HistoryStep::Redone => (Some("redo"), "undo", history.undo_stack().last()),
+        };
+        if let Some(from) = from {
+            tx.execute(
+                "DELETE FROM history WHERE id = (SELECT MAX(id) FROM history WHERE stack = ?1)",
+                [from],
+            )?;
+        }
+        if let Some(edit) = edit {
+            tx.execute(
+                "INSERT INTO history (stack, data) VALUES (?1, ?2)",
+                params![to, serde_json::to_string(edit)?],
+            )?;
+        }
+        // Recording may have dropped the oldest edits from the undo stack.
+        tx.execute(
+            "DELETE FROM history WHERE stack = 'undo' AND id NOT IN
+                 (SELECT id FROM history WHERE stack = 'undo' ORDER BY id DESC LIMIT ?1)",
+            [history.undo_stack().len()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::test_util::todo;
+    use crate::todo::{Change, Edit};
+
+    fn added(id: usize) -> Edit {
+        Edit {
+            description: format!("Added {}", id),
+            changes: vec![Change::Added {
+                index: 0,
+                todo: todo(id, "a"),
+            }],
+        }
+    }
+
+    fn descriptions(edits: &[Edit]) -> Vec<&str> {
+        edits.iter().map(|e| e.description.as_str()).collect()
+    }
+
+    #[test]
+    fn history_rows_follow_each_step() {
+        let mut storage = SqliteStorage::open(":memory:").unwrap();
+        let mut history = History::default();
+        for id in 1..=3 {
+            history.record(added(id));
+            storage
+                .save_history(&history, HistoryStep::Recorded)
+                .unwrap();
+        }
+        history.undo();
+        storage.save_history(&history, HistoryStep::Undone).unwrap();
+        history.undo();
+        storage.save_history(&history, HistoryStep::Undone).unwrap();
+        history.redo();
+        storage.save_history(&history, HistoryStep::Redone).unwrap();
+
+        let loaded = storage.load_history().unwrap();
+        assert_eq!(descriptions(loaded.undo_stack()), ["Added 1", "Added 2"]);
+        assert_eq!(descriptions(loaded.redo_stack()), ["Added 3"]);
+
+        history.record(added(4));
+        storage
+            .save_history(&history, HistoryStep::Recorded)
+            .unwrap();
+        let loaded = storage.load_history().unwrap();
+        assert_eq!(
+            descriptions(loaded.undo_stack()),
+            ["Added 1", "Added 2", "Added 4"]
+        );
+        assert!(loaded.redo_stack().is_empty());
+    }
+}
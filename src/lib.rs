@@ -6,4 +6,5 @@ pub mod ui;
 // Re-export commonly used types
 pub use app::{App, Filter, InputMode};
 pub use core::error::Error;
+pub use core::storage::Storage;
 pub use todo::{Priority, Todo};
@@ -1,64 +1,158 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io, time::Duration};
-use todui::{core::input, ui, App, Error, InputMode};
+use ratatui::{backend::CrosstermBackend, style::Color, Terminal};
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+use todui::{
+    core::{editor, input, JsonStorage},
+    ui, App, Error, InputMode,
+};
 
-fn run_app<B: ratatui::backend::Backend>(
-    terminal: &mut Terminal<B>,
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     mut app: App,
 ) -> Result<(), Error> {
     loop {
-        terminal.draw(|f| ui::render::<B>(f, &app))?;
+        terminal.draw(|f| ui::render::<CrosstermBackend<io::Stdout>>(f, &app))?;
 
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Key(key) = &event {
                 if key.code == KeyCode::Char('q') && matches!(app.input_mode, InputMode::Normal) {
                     return Ok(());
                 }
-                input::handle_input(&mut app, Event::Key(key));
             }
+            input::handle_input(&mut app, event);
+        }
+
+        if let Some(id) = app.editor_request.take() {
+            edit_notes(terminal, &mut app, id)?;
         }
 
         app.update();
     }
 }
 
-fn setup_terminal() -> Result<(Terminal<CrosstermBackend<io::Stdout>>, App), Error> {
+/// Suspends the TUI while the notes of the todo with `id` are edited in an
+/// external editor, then applies the result.
+fn edit_notes(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    id: usize,
+) -> Result<(), Error> {
+    let Some(notes) = app
+        .todos
+        .iter()
+        .find(|todo| todo.id == id)
+        .map(|todo| todo.notes.clone())
+    else {
+        return Ok(());
+    };
+
+    cleanup_terminal(terminal)?;
+    let edited = editor::edit_text(&notes, &format!("note-{}.md", id));
+    *terminal = setup_terminal()?;
+    terminal.clear()?;
+
+    match edited {
+        Ok(notes) => app.set_notes(id, notes),
+        Err(e) => app.show_message(&format!("Failed to edit note: {}", e), Color::Red),
+    }
+    Ok(())
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, Error> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
 
     let backend = CrosstermBackend::new(stdout);
-    let terminal = Terminal::new(backend)?;
-    let app = App::new();
-
-    Ok((terminal, app))
+    Ok(Terminal::new(backend)?)
 }
 
-fn cleanup_terminal(mut terminal: Terminal<CrosstermBackend<io::Stdout>>) -> Result<(), Error> {
+fn cleanup_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<(), Error> {
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Restores `todos.json` from a backup. Without a backup number, the
+/// backups are listed and the user is asked to pick one.
+fn restore_backup(choice: Option<&str>) -> Result<(), Error> {
+    if cfg!(feature = "sqlite") {
+        return Err(Error::Config(
+            "Backups are only kept for todos.json, not for the SQLite database".to_string(),
+        ));
+    }
+
+    let mut storage = JsonStorage::open_default()?;
+    let backups = storage.backups()?;
+    if backups.is_empty() {
+        println!("No backups found.");
+        return Ok(());
+    }
+
+    let choice = match choice {
+        Some(choice) => choice.to_string(),
+        None => {
+            for (i, backup) in backups.iter().enumerate() {
+                println!("{:>3}  {}", i + 1, backup.display());
+            }
+            print!(
+                "Restore which backup? [1-{}, Enter to cancel] ",
+                backups.len()
+            );
+            io::stdout().flush()?;
+            let mut line = String::new();
+            io::stdin().read_line(&mut line)?;
+            line.trim().to_string()
+        }
+    };
+    if choice.is_empty() {
+        return Ok(());
+    }
+
+    let backup = choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| backups.get(n.wrapping_sub(1)))
+        .ok_or_else(|| Error::Config(format!("No backup numbered {}", choice)))?;
+    let count = storage.restore(backup)?;
+    println!("Restored {} todos from {}", count, backup.display());
+    Ok(())
+}
+
 fn main() -> Result<(), Error> {
     let args: Vec<String> = std::env::args().collect();
     if args.contains(&"--version".to_string()) {
         println!("{}", env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
+    if let Some(pos) = args.iter().position(|arg| arg == "--restore") {
+        return restore_backup(args.get(pos + 1).map(String::as_str));
+    }
 
-    let (mut terminal, app) = setup_terminal()?;
-    let result = run_app(&mut terminal, app);
-    cleanup_terminal(terminal)?;
+    let mut terminal = setup_terminal()?;
+    let result = run_app(&mut terminal, App::new());
+    cleanup_terminal(&mut terminal)?;
     result
 }
@@ -0,0 +1,152 @@
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Weekday};
+
+/// Parses a due date relative to `today`. Accepted forms are `today`,
+/// `tomorrow`, weekday names such as `fri` (the next such day after today),
+/// offsets such as `+3d`, `+2w` or `+1m`, and ISO dates such as
+/// `2026-11-02`. A time such as `14:30` may follow the date, or stand on
+/// its own to mean today.
+pub fn parse_due(input: &str, today: NaiveDate) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let mut parts = input.split_whitespace();
+    let first = parts.next()?;
+    let second = parts.next();
+    if parts.next().is_some() {
+        return None;
+    }
+
+    if let Some(time) = parse_time(first) {
+        return second.is_none().then_some((today, Some(time)));
+    }
+
+    let date = parse_date(&first.to_lowercase(), today)?;
+    match second {
+        Some(time) => Some((date, Some(parse_time(time)?))),
+        None => Some((date, None)),
+    }
+}
+
+fn parse_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match input {
+        "today" | "tod" => return Some(today),
+        "tomorrow" | "tom" => return today.checked_add_days(Days::new(1)),
+        _ => {}
+    }
+
+    if let Some(weekday) = parse_weekday(input) {
+        let ahead =
+            (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+        let ahead = if ahead == 0 { 7 } else { ahead };
+        return today.checked_add_days(Days::new(ahead.into()));
+    }
+
+    if let Some(offset) = input.strip_prefix('+') {
+        let unit = offset.chars().last()?;
+        let count: u32 = offset[..offset.len() - unit.len_utf8()].parse().ok()?;
+        return match unit {
+            'd' => today.checked_add_days(Days::new(count.into())),
+            'w' => today.checked_add_days(Days::new(u64::from(count) * 7)),
+            'm' => today.checked_add_months(Months::new(count)),
+            _ => None,
+        };
+    }
+
+    NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()
+}
+
+pub fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_time(input: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(input, "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::test_util::{date, today};
+
+    fn time(h: u32, m: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(h, m, 0)
+    }
+
+    #[test]
+    fn named_days() {
+        assert_eq!(parse_due("today", today()), Some((today(), None)));
+        assert_eq!(parse_due("tod", today()), Some((today(), None)));
+        assert_eq!(
+            parse_due("Tomorrow", today()),
+            Some((date(2026, 10, 15), None))
+        );
+        assert_eq!(parse_due("tom", today()), Some((date(2026, 10, 15), None)));
+    }
+
+    #[test]
+    fn weekdays_are_always_in_the_future() {
+        assert_eq!(parse_due("fri", today()), Some((date(2026, 10, 16), None)));
+        assert_eq!(
+            parse_due("monday", today()),
+            Some((date(2026, 10, 19), None))
+        );
+        // The same weekday as today means next week.
+        assert_eq!(parse_due("wed", today()), Some((date(2026, 10, 21), None)));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_due("+0d", today()), Some((today(), None)));
+        assert_eq!(parse_due("+3d", today()), Some((date(2026, 10, 17), None)));
+        assert_eq!(parse_due("+2w", today()), Some((date(2026, 10, 28), None)));
+        assert_eq!(parse_due("+1m", today()), Some((date(2026, 11, 14), None)));
+        // Months clamp to the last day of shorter months.
+        assert_eq!(
+            parse_due("+1m", date(2026, 1, 31)),
+            Some((date(2026, 2, 28), None))
+        );
+        assert_eq!(parse_due("+3y", today()), None);
+        assert_eq!(parse_due("+d", today()), None);
+        assert_eq!(parse_due("+", today()), None);
+        assert_eq!(parse_due("+-1d", today()), None);
+    }
+
+    #[test]
+    fn iso_dates() {
+        assert_eq!(
+            parse_due("2026-11-02", today()),
+            Some((date(2026, 11, 2), None))
+        );
+        assert_eq!(parse_due("2026-02-30", today()), None);
+        assert_eq!(parse_due("02-11-2026", today()), None);
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(
+            parse_due("fri 17:00", today()),
+            Some((date(2026, 10, 16), time(17, 0)))
+        );
+        assert_eq!(parse_due("14:30", today()), Some((today(), time(14, 30))));
+        assert_eq!(
+            parse_due("  tomorrow   9:05 ", today()),
+            Some((date(2026, 10, 15), time(9, 5)))
+        );
+        assert_eq!(parse_due("fri 25:00", today()), None);
+        assert_eq!(parse_due("14:30 fri", today()), None);
+        assert_eq!(parse_due("fri 17:00 extra", today()), None);
+    }
+
+    #[test]
+    fn rejects_unknown_input() {
+        assert_eq!(parse_due("", today()), None);
+        assert_eq!(parse_due("someday", today()), None);
+        assert_eq!(parse_due("next week", today()), None);
+    }
+}
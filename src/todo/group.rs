@@ -0,0 +1,88 @@
+use super::{Priority, Todo};
+use chrono::{Datelike, Days, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// How the list is split into sections.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    #[default]
+    None,
+    Priority,
+    /// By the first tag of each todo.
+    Tag,
+    /// Overdue, today, this week, later, and no due date.
+    Due,
+    /// Open todos, then by the day they were completed, latest first.
+    Completed,
+}
+
+impl GroupBy {
+    /// The grouping after this one when cycling through them.
+    pub fn next(self) -> GroupBy {
+        match self {
+            GroupBy::None => GroupBy::Priority,
+            GroupBy::Priority => GroupBy::Tag,
+            GroupBy::Tag => GroupBy::Due,
+            GroupBy::Due => GroupBy::Completed,
+            GroupBy::Completed => GroupBy::None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupBy::None => "None",
+            GroupBy::Priority => "Priority",
+            GroupBy::Tag => "Tag",
+            GroupBy::Due => "Due",
+            GroupBy::Completed => "Completed",
+        }
+    }
+
+    /// The section `todo` belongs in, as a rank to order sections by and a
+    /// title. Returns `None` when not grouping.
+    pub fn group(self, todo: &Todo, now: NaiveDateTime) -> Option<(i64, String)> {
+        let today = now.date();
+        Some(match self {
+            GroupBy::None => return None,
+            GroupBy::Priority => match todo.priority {
+                Priority::High => (0, "High".to_string()),
+                Priority::Medium => (1, "Medium".to_string()),
+                Priority::Low => (2, "Low".to_string()),
+            },
+            // Tags sort by title within the same rank.
+            GroupBy::Tag => match todo.tags.first() {
+                Some(tag) => (0, tag.to_lowercase()),
+                None => (1, "Untagged".to_string()),
+            },
+            GroupBy::Due => {
+                let end_of_week = today
+                    .checked_add_days(Days::new(
+                        6 - u64::from(today.weekday().num_days_from_monday()),
+                    ))
+                    .unwrap_or(today);
+                match todo.due {
+                    _ if todo.is_overdue(now) => (0, "Overdue".to_string()),
+                    Some(due) if due == today => (1, "Today".to_string()),
+                    Some(due) if due > today && due <= end_of_week => (2, "This week".to_string()),
+                    Some(due) if due > end_of_week => (3, "Later".to_string()),
+                    Some(_) => (4, "Past".to_string()),
+                    None => (5, "No due date".to_string()),
+                }
+            }
+            GroupBy::Completed => match todo.completed_at {
+                _ if !todo.completed => (i64::MIN, "Open".to_string()),
+                Some(at) => {
+                    let day = at.date_naive();
+                    let title = if day == today {
+                        "Completed today".to_string()
+                    } else {
+                        format!("Completed {}", day.format("%Y-%m-%d"))
+                    };
+                    (-i64::from(day.num_days_from_ce()), title)
+                }
+                None => (i64::MAX, "Completed".to_string()),
+            },
+        })
+    }
+}
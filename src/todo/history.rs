@@ -0,0 +1,224 @@
+use super::Todo;
+use serde::{Deserialize, Serialize};
+
+/// Number of edits kept for undo.
+const HISTORY_LIMIT: usize = 100;
+
+/// A single change to the todo list, recorded with enough context to be
+/// applied again or reverted.
+#[derive(Serialize, Deserialize, Clone)]
+pub enum Change {
+    Added { index: usize, todo: Todo },
+    Removed { index: usize, todo: Todo },
+    Updated { before: Box<Todo>, after: Box<Todo> },
+}
+
+impl Change {
+    /// The change that reverts this one.
+    pub fn inverse(&self) -> Change {
+        match self {
+            Change::Added { index, todo } => Change::Removed {
+                index: *index,
+                todo: todo.clone(),
+            },
+            Change::Removed { index, todo } => Change::Added {
+                index: *index,
+                todo: todo.clone(),
+            },
+            Change::Updated { before, after } => Change::Updated {
+                before: after.clone(),
+                after: before.clone(),
+            },
+        }
+    }
+
+    /// Applies the change to `todos`. Changes to todos that no longer exist
+    /// are ignored, which can happen if the file was edited outside todui.
+    pub fn apply(&self, todos: &mut Vec<Todo>) {
+        match self {
+            Change::Added { index, todo } => {
+                if !todos.iter().any(|t| t.id == todo.id) {
+                    todos.insert((*index).min(todos.len()), todo.clone());
+                }
+            }
+            Change::Removed { todo, .. } => todos.retain(|t| t.id != todo.id),
+            Change::Updated { before, after } => {
+                if let Some(todo) = todos.iter_mut().find(|t| t.id == after.id) {
+                    // Folding is not recorded as an edit, so unless this
+                    // change folded or unfolded the todo, keep its current
+                    // state rather than the one snapshotted back then.
+                    let collapsed = if before.collapsed == after.collapsed {
+                        todo.collapsed
+                    } else {
+                        after.collapsed
+                    };
+                    *todo = (**after).clone();
+                    todo.collapsed = collapsed;
+                }
+            }
+        }
+    }
+}
+
+/// One user action, made up of the changes it caused.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Edit {
+    pub description: String,
+    pub changes: Vec<Change>,
+}
+
+/// What a `History` just went through, so that storage backends can write
+/// only what changed.
+#[derive(Clone, Copy)]
+pub enum HistoryStep {
+    /// An edit was recorded, clearing the redo stack and possibly dropping
+    /// the oldest edit.
+    Recorded,
+    /// The last edit moved from the undo stack to the redo stack.
+    Undone,
+    /// The last undone edit moved back to the undo stack.
+    Redone,
+}
+
+/// Bounded undo and redo stacks of edits.
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    /// A history with the given stacks, the most recent edit last in each.
+    pub fn from_stacks(undo: Vec<Edit>, redo: Vec<Edit>) -> Self {
+        Self { undo, redo }
+    }
+
+    pub fn undo_stack(&self) -> &[Edit] {
+        &self.undo
+    }
+
+    pub fn redo_stack(&self) -> &[Edit] {
+        &self.redo
+    }
+
+    /// Records a new edit. Anything that could have been redone is dropped.
+    pub fn record(&mut self, edit: Edit) {
+        self.undo.push(edit);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Takes the most recent edit off the undo stack and moves it to the
+    /// redo stack.
+    pub fn undo(&mut self) -> Option<&Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit);
+        self.redo.last()
+    }
+
+    /// Takes the most recently undone edit off the redo stack and moves it
+    /// back to the undo stack.
+    pub fn redo(&mut self) -> Option<&Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit);
+        self.undo.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::test_util::todo;
+
+    fn rename(todos: &mut [Todo], id: usize, description: &str) -> Edit {
+        let todo = todos.iter_mut().find(|t| t.id == id).unwrap();
+        let before = Box::new(todo.clone());
+        todo.description = description.to_string();
+        Edit {
+            description: format!("Renamed {}", id),
+            changes: vec![Change::Updated {
+                before,
+                after: Box::new(todo.clone()),
+            }],
+        }
+    }
+
+    fn undo(history: &mut History, todos: &mut Vec<Todo>) {
+        for change in history.undo().unwrap().changes.iter().rev() {
+            change.inverse().apply(todos);
+        }
+    }
+
+    fn redo(history: &mut History, todos: &mut Vec<Todo>) {
+        for change in &history.redo().unwrap().changes {
+            change.apply(todos);
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_move_edits_between_stacks() {
+        let mut todos = vec![todo(1, "a")];
+        let mut history = History::default();
+        let edit = rename(&mut todos, 1, "b");
+        history.record(edit);
+        let edit = rename(&mut todos, 1, "c");
+        history.record(edit);
+
+        undo(&mut history, &mut todos);
+        assert_eq!(todos[0].description, "b");
+        undo(&mut history, &mut todos);
+        assert_eq!(todos[0].description, "a");
+        assert!(history.undo().is_none());
+
+        redo(&mut history, &mut todos);
+        assert_eq!(todos[0].description, "b");
+        assert_eq!(history.undo_stack().len(), 1);
+        assert_eq!(history.redo_stack().len(), 1);
+    }
+
+    #[test]
+    fn recording_drops_the_redo_stack() {
+        let mut todos = vec![todo(1, "a")];
+        let mut history = History::default();
+        let edit = rename(&mut todos, 1, "b");
+        history.record(edit);
+        undo(&mut history, &mut todos);
+
+        let edit = rename(&mut todos, 1, "c");
+        history.record(edit);
+        assert!(history.redo().is_none());
+    }
+
+    #[test]
+    fn the_oldest_edits_are_dropped_past_the_limit() {
+        let mut todos = vec![todo(1, "a")];
+        let mut history = History::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            let edit = rename(&mut todos, 1, &i.to_string());
+            history.record(edit);
+        }
+        assert_eq!(history.undo_stack().len(), HISTORY_LIMIT);
+        let Change::Updated { before, .. } = &history.undo_stack()[0].changes[0] else {
+            panic!("expected an update");
+        };
+        assert_eq!(before.description, "4");
+    }
+
+    #[test]
+    fn undo_keeps_the_current_folding() {
+        let mut todos = vec![todo(1, "a")];
+        let mut history = History::default();
+        let edit = rename(&mut todos, 1, "b");
+        history.record(edit);
+
+        todos[0].collapsed = true;
+        undo(&mut history, &mut todos);
+        assert_eq!(todos[0].description, "a");
+        assert!(todos[0].collapsed);
+        redo(&mut history, &mut todos);
+        assert!(todos[0].collapsed);
+    }
+}
@@ -1,3 +1,15 @@
+mod due;
+mod group;
+mod history;
 mod model;
+mod recurrence;
+mod sort;
+#[cfg(test)]
+pub(crate) mod test_util;
 
+pub use due::{parse_due, parse_weekday};
+pub use group::GroupBy;
+pub use history::{Change, Edit, History, HistoryStep};
 pub use model::{Priority, Todo};
+pub use recurrence::Recurrence;
+pub use sort::SortMode;
@@ -1,7 +1,8 @@
-use chrono::{DateTime, Local};
+use super::Recurrence;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Priority {
     High,
     Medium,
@@ -18,4 +19,61 @@ pub struct Todo {
     pub completed_at: Option<DateTime<Local>>,
     pub tags: Vec<String>,
     pub notes: String,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub due_time: Option<NaiveTime>,
+    /// Set on the open occurrence of a repeating todo. Completing it moves
+    /// the rule on to the next occurrence.
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Id of the todo this one is a subtask of.
+    #[serde(default)]
+    pub parent: Option<usize>,
+    /// Whether the subtasks of this todo are hidden in the list.
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Ids of the todos that have to be completed before this one.
+    #[serde(default)]
+    pub blocked_by: Vec<usize>,
+    /// Place in the manual sort order; lower comes first. Todos with the
+    /// same position keep the order they are stored in.
+    #[serde(default)]
+    pub position: i64,
+    /// When the todo was moved to the trash. Trashed todos are kept until
+    /// purged but left out everywhere else.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Local>>,
+}
+
+impl Todo {
+    /// When the todo is due. Without a time, it is due at the end of the day.
+    pub fn due_at(&self) -> Option<NaiveDateTime> {
+        let time = self
+            .due_time
+            .unwrap_or(NaiveTime::from_hms_opt(23, 59, 59)?);
+        Some(self.due?.and_time(time))
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        !self.completed && self.due_at().is_some_and(|due| due < now)
+    }
+
+    pub fn is_due_today(&self, today: NaiveDate) -> bool {
+        !self.completed && self.due == Some(today)
+    }
+
+    /// Formats the due date, and the time if there is one, for display and
+    /// editing. The result can be read back by `parse_due`.
+    pub fn due_label(&self) -> Option<String> {
+        let due = self.due?;
+        Some(match self.due_time {
+            Some(time) => format!("{} {}", due.format("%Y-%m-%d"), time.format("%H:%M")),
+            None => due.format("%Y-%m-%d").to_string(),
+        })
+    }
 }
@@ -0,0 +1,213 @@
+use super::parse_weekday;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// How a todo repeats once it is completed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Recurrence {
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    Weekly(Vec<Weekday>),
+    /// On the given day of every month, or the last day of shorter months.
+    Monthly(u32),
+    /// The given number of days after the previous occurrence was completed.
+    AfterCompletion(u32),
+}
+
+impl Recurrence {
+    /// Parses `daily`, `weekdays`, `weekly mon,thu`, `monthly 15` or
+    /// `every 3d`. Without days, `weekly` and `monthly` repeat on the
+    /// weekday and day of month of `today`.
+    pub fn parse(input: &str, today: NaiveDate) -> Option<Recurrence> {
+        let input = input.trim().to_lowercase();
+        let (kind, rest) = input.split_once(' ').unwrap_or((&input, ""));
+        let rest = rest.trim();
+
+        match kind {
+            "daily" if rest.is_empty() => Some(Recurrence::Daily),
+            "weekdays" if rest.is_empty() => Some(Recurrence::Weekdays),
+            "weekly" if rest.is_empty() => Some(Recurrence::Weekly(vec![today.weekday()])),
+            "weekly" => {
+                let mut days = rest
+                    .split(',')
+                    .map(|day| parse_weekday(day.trim()))
+                    .collect::<Option<Vec<_>>>()?;
+                days.sort_by_key(|day| day.num_days_from_monday());
+                days.dedup();
+                Some(Recurrence::Weekly(days))
+            }
+            "monthly" if rest.is_empty() => Some(Recurrence::Monthly(today.day())),
+            "monthly" => match rest.parse() {
+                Ok(day @ 1..=31) => Some(Recurrence::Monthly(day)),
+                _ => None,
+            },
+            "every" => match rest.trim_end_matches('d').parse() {
+                Ok(days) if days > 0 => Some(Recurrence::AfterCompletion(days)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Formats the rule the way `parse` reads it.
+    pub fn label(&self) -> String {
+        match self {
+            Recurrence::Daily => "daily".to_string(),
+            Recurrence::Weekdays => "weekdays".to_string(),
+            Recurrence::Weekly(days) => format!(
+                "weekly {}",
+                days.iter()
+                    .map(|day| day.to_string().to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Recurrence::Monthly(day) => format!("monthly {}", day),
+            Recurrence::AfterCompletion(days) => format!("every {}d", days),
+        }
+    }
+
+    /// Due date of the occurrence after one that was due on `due` and
+    /// completed on `completed`. Scheduled rules continue from the later of
+    /// the two, so completing a late todo does not spawn one that is
+    /// already overdue.
+    pub fn next_due(&self, due: Option<NaiveDate>, completed: NaiveDate) -> Option<NaiveDate> {
+        let base = due.map_or(completed, |due| due.max(completed));
+        let mut dates = base.iter_days().skip(1).take(366);
+        match self {
+            Recurrence::Daily => base.succ_opt(),
+            Recurrence::Weekdays => {
+                dates.find(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+            }
+            Recurrence::Weekly(days) => dates.find(|date| days.contains(&date.weekday())),
+            Recurrence::Monthly(day) => dates.find(|date| {
+                date.day() == *day || (date.day() < *day && is_last_day_of_month(*date))
+            }),
+            Recurrence::AfterCompletion(days) => {
+                completed.checked_add_days(Days::new((*days).into()))
+            }
+        }
+    }
+}
+
+fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt()
+        .is_some_and(|next| next.month() != date.month())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::test_util::{date, today};
+
+    fn parse(input: &str) -> Option<Recurrence> {
+        Recurrence::parse(input, today())
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(parse("daily"), Some(Recurrence::Daily));
+        assert_eq!(parse("  Weekdays "), Some(Recurrence::Weekdays));
+        assert_eq!(parse("monthly 15"), Some(Recurrence::Monthly(15)));
+        assert_eq!(parse("every 3d"), Some(Recurrence::AfterCompletion(3)));
+        assert_eq!(parse("every 3"), Some(Recurrence::AfterCompletion(3)));
+    }
+
+    #[test]
+    fn weekly_days_are_sorted_and_deduplicated() {
+        assert_eq!(
+            parse("weekly thu, mon,thursday"),
+            Some(Recurrence::Weekly(vec![Weekday::Mon, Weekday::Thu]))
+        );
+    }
+
+    #[test]
+    fn defaults_come_from_today() {
+        assert_eq!(
+            parse("weekly"),
+            Some(Recurrence::Weekly(vec![Weekday::Wed]))
+        );
+        assert_eq!(parse("monthly"), Some(Recurrence::Monthly(14)));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for input in [
+            "",
+            "hourly",
+            "daily 2",
+            "weekdays mon",
+            "weekly mon,funday",
+            "weekly mon,",
+            "monthly 0",
+            "monthly 32",
+            "monthly last",
+            "every 0d",
+            "every",
+            "every 3w",
+        ] {
+            assert!(parse(input).is_none(), "{:?} should not parse", input);
+        }
+    }
+
+    #[test]
+    fn labels_parse_back_to_the_same_rule() {
+        for input in [
+            "daily",
+            "weekdays",
+            "weekly mon,fri",
+            "monthly 31",
+            "every 10d",
+        ] {
+            let rule = parse(input).unwrap();
+            assert_eq!(rule.label(), input);
+            assert_eq!(parse(&rule.label()), Some(rule));
+        }
+    }
+
+    #[test]
+    fn next_due_dates() {
+        let friday = date(2026, 10, 16);
+        assert_eq!(
+            Recurrence::Daily.next_due(None, today()),
+            Some(date(2026, 10, 15))
+        );
+        assert_eq!(
+            Recurrence::Weekdays.next_due(Some(friday), today()),
+            Some(date(2026, 10, 19))
+        );
+        assert_eq!(
+            Recurrence::Weekly(vec![Weekday::Mon, Weekday::Thu]).next_due(None, today()),
+            Some(date(2026, 10, 15))
+        );
+        assert_eq!(
+            Recurrence::AfterCompletion(3).next_due(Some(friday), today()),
+            Some(date(2026, 10, 17))
+        );
+    }
+
+    #[test]
+    fn late_completion_continues_from_the_completion_date() {
+        assert_eq!(
+            Recurrence::Daily.next_due(Some(date(2026, 10, 1)), today()),
+            Some(date(2026, 10, 15))
+        );
+    }
+
+    #[test]
+    fn monthly_falls_back_to_the_last_day_of_short_months() {
+        let rule = Recurrence::Monthly(31);
+        assert_eq!(
+            rule.next_due(Some(date(2026, 1, 31)), date(2026, 1, 31)),
+            Some(date(2026, 2, 28))
+        );
+        assert_eq!(
+            rule.next_due(Some(date(2026, 2, 28)), date(2026, 2, 28)),
+            Some(date(2026, 3, 31))
+        );
+        assert_eq!(
+            rule.next_due(Some(date(2026, 3, 31)), date(2026, 3, 31)),
+            Some(date(2026, 4, 30))
+        );
+    }
+}
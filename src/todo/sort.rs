@@ -0,0 +1,78 @@
+use super::{Priority, Todo};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// The order todos are listed in. Subtasks are sorted among their siblings.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// The order todos were added in, as rearranged by hand.
+    #[default]
+    Manual,
+    /// Highest priority first.
+    Priority,
+    /// Earliest due first; todos without a due date last.
+    Due,
+    /// Newest first.
+    Created,
+    /// Open todos first, then the most recently completed.
+    Completed,
+    /// By description, ignoring case.
+    Alphabetical,
+}
+
+impl SortMode {
+    /// The sort mode after this one when cycling through them.
+    pub fn next(self) -> SortMode {
+        match self {
+            SortMode::Manual => SortMode::Priority,
+            SortMode::Priority => SortMode::Due,
+            SortMode::Due => SortMode::Created,
+            SortMode::Created => SortMode::Completed,
+            SortMode::Completed => SortMode::Alphabetical,
+            SortMode::Alphabetical => SortMode::Manual,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Manual => "Manual",
+            SortMode::Priority => "Priority",
+            SortMode::Due => "Due",
+            SortMode::Created => "Created",
+            SortMode::Completed => "Completed",
+            SortMode::Alphabetical => "A-Z",
+        }
+    }
+
+    /// Compares two todos; todos that compare equal keep their order.
+    pub fn compare(self, a: &Todo, b: &Todo) -> Ordering {
+        match self {
+            SortMode::Manual => a.position.cmp(&b.position),
+            SortMode::Priority => priority_rank(&a.priority).cmp(&priority_rank(&b.priority)),
+            SortMode::Due => match (a.due_at(), b.due_at()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            SortMode::Created => b.created_at.cmp(&a.created_at),
+            SortMode::Completed => a
+                .completed
+                .cmp(&b.completed)
+                .then_with(|| b.completed_at.cmp(&a.completed_at)),
+            SortMode::Alphabetical => a
+                .description
+                .to_lowercase()
+                .cmp(&b.description.to_lowercase()),
+        }
+    }
+}
+
+fn priority_rank(priority: &Priority) -> u8 {
+    match priority {
+        Priority::High => 0,
+        Priority::Medium => 1,
+        Priority::Low => 2,
+    }
+}
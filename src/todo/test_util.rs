@@ -0,0 +1,35 @@
+//! Helpers shared by unit tests.
+
+use super::{Priority, Todo};
+use chrono::{Local, NaiveDate};
+
+/// An open, medium priority todo with nothing else set.
+pub fn todo(id: usize, description: &str) -> Todo {
+    Todo {
+        id,
+        description: description.to_string(),
+        completed: false,
+        priority: Priority::Medium,
+        created_at: Local::now(),
+        completed_at: None,
+        tags: Vec::new(),
+        notes: String::new(),
+        due: None,
+        due_time: None,
+        recurrence: None,
+        parent: None,
+        collapsed: false,
+        blocked_by: Vec::new(),
+        position: 0,
+        deleted_at: None,
+    }
+}
+
+/// The date tests treat as today, a Wednesday.
+pub fn today() -> NaiveDate {
+    date(2026, 10, 14)
+}
+
+pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
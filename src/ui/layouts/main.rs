@@ -7,12 +7,13 @@ pub struct AppLayout {
     pub title: Rect,
     pub tabs: Rect,
     pub content: Rect,
+    pub details: Option<Rect>,
     pub status: Rect,
     pub input: Rect,
     pub message: Rect,
 }
 
-pub fn create_main_layout(f: &Frame) -> AppLayout {
+pub fn create_main_layout(f: &Frame, show_details: bool) -> AppLayout {
     let areas = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -25,10 +26,21 @@ pub fn create_main_layout(f: &Frame) -> AppLayout {
         ])
         .split(f.area());
 
+    let (content, details) = if show_details {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(areas[2]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (areas[2], None)
+    };
+
     AppLayout {
         title: areas[0],
         tabs: areas[1],
-        content: areas[2],
+        content,
+        details,
         status: areas[3],
         input: areas[4],
         message: areas[5],
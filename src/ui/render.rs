@@ -1,29 +1,65 @@
+use crate::app::Deletion;
 use crate::{App, InputMode};
 use ratatui::{backend::Backend, widgets::Clear, Frame};
 
 use super::{
     layouts::{centered_rect, create_main_layout},
     widgets::{
-        render_filter_tabs, render_help_popup, render_input, render_message, render_status,
-        render_title, render_todo_list,
+        render_confirm, render_details, render_filter_tabs, render_help_popup, render_input,
+        render_message, render_note_editor, render_status, render_tag_browser, render_title,
+        render_todo_list, render_trash,
     },
 };
 
 pub fn render<B: Backend>(f: &mut Frame, app: &App) {
-    let layout = create_main_layout(f);
+    let layout = create_main_layout(f, app.show_details);
 
     render_title(f, layout.title);
-    render_filter_tabs(f, &app.filter, layout.tabs);
+    render_filter_tabs(f, &app.filter, &app.views, layout.tabs);
     render_todo_list(f, app, layout.content);
+    if let Some(area) = layout.details {
+        render_details(f, app, area);
+    }
 
     // Show detailed help if in help mode
     if matches!(app.input_mode, InputMode::Help) {
-        let area = centered_rect(20, 35, f.area());
+        let area = centered_rect(40, 60, f.area());
         f.render_widget(Clear, area);
         render_help_popup(f, area);
     }
 
     render_status(f, app, layout.status);
     render_input(f, &app.input, &app.input_mode, layout.input);
+
+    if matches!(app.input_mode, InputMode::AddingNote) {
+        let area = centered_rect(60, 60, f.area());
+        f.render_widget(Clear, area);
+        render_note_editor(f, &app.input, area);
+    }
+
+    if matches!(app.input_mode, InputMode::BrowsingTags) {
+        let area = centered_rect(40, 60, f.area());
+        f.render_widget(Clear, area);
+        render_tag_browser(f, app, area);
+    }
+
+    // Keep the trash showing while a purge from it is being confirmed.
+    if matches!(app.input_mode, InputMode::BrowsingTrash)
+        || matches!(
+            app.pending_deletion,
+            Some(Deletion::Purge | Deletion::EmptyTrash)
+        )
+    {
+        let area = centered_rect(60, 60, f.area());
+        f.render_widget(Clear, area);
+        render_trash(f, app, area);
+    }
+
+    if matches!(app.input_mode, InputMode::ConfirmingDelete) {
+        let area = centered_rect(50, 20, f.area());
+        f.render_widget(Clear, area);
+        render_confirm(f, &app.deletion_prompt(), area);
+    }
+
     render_message(f, &app.message, &app.message_timeout, layout.message);
 }
@@ -0,0 +1,21 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render_confirm(f: &mut Frame, prompt: &str, area: Rect) {
+    let paragraph = Paragraph::new(prompt)
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title(" Confirm ")
+                .title_bottom(" y: delete | n: cancel ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        );
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,105 @@
+use crate::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render_details(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Blue))
+        .title(Span::styled(
+            " Details ",
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let Some(todo) = app.get_selected_todo() else {
+        f.render_widget(
+            Paragraph::new("No todo selected")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block),
+            area,
+        );
+        return;
+    };
+
+    let label = Style::default().fg(Color::Gray);
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<10}", name), label),
+            Span::raw(value),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::styled(
+            todo.description.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Line::raw(""),
+        field("Id", format!("#{}", todo.id)),
+        field(
+            "Priority",
+            match todo.priority {
+                crate::Priority::High => "High",
+                crate::Priority::Medium => "Medium",
+                crate::Priority::Low => "Low",
+            }
+            .to_string(),
+        ),
+        field(
+            "Created",
+            todo.created_at.format("%Y-%m-%d %H:%M").to_string(),
+        ),
+    ];
+    if let Some(completed_at) = todo.completed_at {
+        lines.push(field(
+            "Completed",
+            completed_at.format("%Y-%m-%d %H:%M").to_string(),
+        ));
+    }
+    if let Some(due) = todo.due_label() {
+        lines.push(field("Due", due));
+    }
+    if let Some(recurrence) = &todo.recurrence {
+        lines.push(field("Repeats", recurrence.label()));
+    }
+    if !todo.tags.is_empty() {
+        lines.push(field("Tags", todo.tags.join(", ")));
+    }
+    if !todo.blocked_by.is_empty() {
+        lines.push(field(
+            "Blocked by",
+            todo.blocked_by
+                .iter()
+                .map(|id| format!("#{}", id))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+
+    lines.push(Line::raw(""));
+    if todo.notes.is_empty() {
+        lines.push(Line::styled(
+            "No notes. Press 'o' to add one.",
+            Style::default().fg(Color::DarkGray),
+        ));
+    } else {
+        lines.extend(todo.notes.lines().map(|line| Line::raw(line.to_string())));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block);
+    // Counted with wrapping, so that long notes can be scrolled to the end.
+    let height = paragraph.line_count(area.width.saturating_sub(2));
+    let max_scroll = height.saturating_sub(area.height as usize) as u16;
+    app.detail_max_scroll.set(max_scroll);
+    let scroll = app.detail_scroll.min(max_scroll);
+    f.render_widget(paragraph.scroll((scroll, 0)), area);
+}
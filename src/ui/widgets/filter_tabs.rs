@@ -1,17 +1,42 @@
-use crate::app::Filter;
+use crate::{app::Filter, core::config::View};
 use ratatui::{
     style::{Color, Modifier, Style},
     widgets::{Block, Borders, Tabs},
     Frame,
 };
 
-pub fn render_filter_tabs(f: &mut Frame, filter: &Filter, area: ratatui::layout::Rect) {
-    let filters = vec!["All", "Active", "Completed", "High Priority"];
+pub fn render_filter_tabs(
+    f: &mut Frame,
+    filter: &Filter,
+    views: &[View],
+    area: ratatui::layout::Rect,
+) {
+    let mut filters = vec![
+        "All".to_string(),
+        "Active".to_string(),
+        "Completed".to_string(),
+        "High Priority".to_string(),
+        "Due Soon".to_string(),
+        "Ready".to_string(),
+    ];
+    filters.extend(views.iter().map(|view| view.name.clone()));
     let filter_index = match filter {
         Filter::All => 0,
         Filter::Active => 1,
         Filter::Completed => 2,
         Filter::HighPriority => 3,
+        Filter::DueSoon => 4,
+        Filter::Ready => 5,
+        Filter::View { name, .. } => {
+            6 + views
+                .iter()
+                .position(|view| view.name == *name)
+                .unwrap_or_default()
+        }
+        Filter::Query { source, .. } => {
+            filters.push(format!("Query: {}", source));
+            filters.len() - 1
+        }
     };
 
     let tabs = Tabs::new(filters)
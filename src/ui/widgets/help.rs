@@ -12,14 +12,39 @@ pub fn render_help_popup(f: &mut Frame, area: Rect) {
         "─────────────────",
         "q      - Quit application",
         "a      - Add todo",
+        "e      - Edit todo description",
         "j/↓    - Move selection down",
         "k/↑    - Move selection up",
+        "J/K    - Move todo down/up (manual sort)",
         "Space  - Toggle completion",
-        "d      - Delete selected todo",
+        "d      - Move todo to the trash",
+        "x      - Mark/unmark todo",
+        "v      - Start/stop marking a range",
+        "m      - Move todo under another (by id)",
         "p      - Cycle priority",
         "t      - Add/edit tags",
-        "n      - Add/edit note",
+        "o      - Edit note (Ctrl-s to save)",
+        "E      - Edit note in $VISUAL/$EDITOR",
+        "i      - Toggle details pane",
+        "PgUp/PgDn - Scroll details pane",
+        "D      - Set/clear due date",
+        "r      - Set/clear repeat rule",
+        "b      - Set/clear blocking todos",
+        ">/<    - Indent/outdent as subtask",
+        "z      - Fold/unfold subtasks",
+        "u      - Undo last change",
+        "Ctrl-r - Redo last undone change",
+        "/      - Search (Esc clears)",
+        "n/N    - Jump to next/previous match",
         "Tab    - Cycle through filters",
+        "s      - Cycle sort order (of the view, if shown)",
+        "g      - Cycle grouping",
+        "f      - Filter by query (Ex: tag:work !done)",
+        "V      - Save the current query as a view",
+        "X      - Delete the current view",
+        "#      - Browse tags and filter by them",
+        "T      - Browse the trash",
+        "Esc    - Clear marks, then search",
         "?      - Toggle this help",
         "",
         "Press Esc to close help",
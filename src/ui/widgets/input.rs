@@ -1,4 +1,4 @@
-use crate::app::InputMode;
+use crate::app::{InputMode, TextInput};
 use ratatui::{
     style::{Color, Style},
     widgets::{Block, Borders, Paragraph},
@@ -7,7 +7,7 @@ use ratatui::{
 
 pub fn render_input(
     f: &mut Frame,
-    input: &str,
+    input: &TextInput,
     input_mode: &InputMode,
     area: ratatui::layout::Rect,
 ) {
@@ -19,17 +19,72 @@ pub fn render_input(
     let input_title = match input_mode {
         InputMode::Normal => " Press 'a' to add a new todo ",
         InputMode::AddingTodo => " Enter a todo description... ",
-        InputMode::AddingTags => " Enter a comma-separated list of tags... (Ex: work, personal) ",
-        InputMode::AddingNote => " Enter a note... ",
+        InputMode::EditingTodo => " Edit the todo description... ",
+        InputMode::AddingTags => {
+            " Enter a comma-separated list of tags... (Ex: work, personal; Tab completes) "
+        }
+        InputMode::AddingNote => " Editing note... ",
+        InputMode::SettingDue => {
+            " Enter a due date... (Ex: tomorrow, fri 17:00, +3d, 2026-11-02; empty to clear) "
+        }
+        InputMode::SettingRecurrence => {
+            " Enter a repeat rule... (Ex: daily, weekdays, weekly mon,thu, monthly 15, every 3d; empty to stop) "
+        }
+        InputMode::SettingBlockers => {
+            " Enter the ids of the todos this one waits on... (Ex: 3, 7; empty to clear) "
+        }
+        InputMode::TaggingMarked => {
+            " Enter tags to add to the marked todos... (Ex: work, -home removes home) "
+        }
+        InputMode::MovingTodos => {
+            " Enter the id of the todo to move under... (Ex: 3; empty for the top level) "
+        }
+        InputMode::Searching => " Search descriptions, tags and notes... (Enter to keep, Esc to clear) ",
+        InputMode::EnteringQuery => {
+            " Enter a filter query... (Ex: tag:work pri:high due:<7d !done \"text\"; empty for all) "
+        }
+        InputMode::NamingView => " Enter a name to save the current query as a view... ",
+        InputMode::BrowsingTags => " Browsing tags ",
+        InputMode::ConfirmingDelete => " Confirm deletion... (y to delete, n or Esc to cancel) ",
+        InputMode::BrowsingTrash => " Browsing trash ",
         InputMode::Help => " Help Mode ",
     };
 
-    let input = Paragraph::new(input.to_string()).style(input_style).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(input_style)
-            .title(input_title),
-    );
+    // Multi-line input is edited in its own popup.
+    let text = if input.is_multiline() {
+        ""
+    } else {
+        input.text()
+    };
+
+    // Scroll horizontally just far enough to keep the cursor in view.
+    let width = area.width.saturating_sub(2) as usize;
+    let (_, cursor) = input.cursor_position();
+    let scroll = (cursor + 1).saturating_sub(width);
+
+    let paragraph = Paragraph::new(text)
+        .style(input_style)
+        .scroll((0, scroll as u16))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(input_style)
+                .title(input_title),
+        );
+
+    f.render_widget(paragraph, area);
 
-    f.render_widget(input, area);
+    if !input.is_multiline()
+        && width > 0
+        && !matches!(
+            input_mode,
+            InputMode::Normal
+                | InputMode::BrowsingTags
+                | InputMode::ConfirmingDelete
+                | InputMode::BrowsingTrash
+                | InputMode::Help
+        )
+    {
+        f.set_cursor_position((area.x + 1 + (cursor - scroll) as u16, area.y + 1));
+    }
 }
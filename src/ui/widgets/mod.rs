@@ -1,15 +1,25 @@
+mod confirm;
+mod details;
 mod filter_tabs;
 mod help;
 mod input;
 mod message;
+mod note_editor;
 mod status;
+mod tag_browser;
 mod title;
 mod todo_list;
+mod trash;
 
+pub use confirm::render_confirm;
+pub use details::render_details;
 pub use filter_tabs::render_filter_tabs;
 pub use help::render_help_popup;
 pub use input::render_input;
 pub use message::render_message;
+pub use note_editor::render_note_editor;
 pub use status::render_status;
+pub use tag_browser::render_tag_browser;
 pub use title::render_title;
 pub use todo_list::render_todo_list;
+pub use trash::render_trash;
@@ -0,0 +1,36 @@
+use crate::app::TextInput;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+pub fn render_note_editor(f: &mut Frame, input: &TextInput, area: Rect) {
+    // Scroll just far enough to keep the cursor in view.
+    let height = area.height.saturating_sub(2) as usize;
+    let width = area.width.saturating_sub(2) as usize;
+    let (line, column) = input.cursor_position();
+    let scroll_y = (line + 1).saturating_sub(height);
+    let scroll_x = (column + 1).saturating_sub(width);
+
+    let editor = Paragraph::new(input.text())
+        .style(Style::default().fg(Color::White))
+        .scroll((scroll_y as u16, scroll_x as u16))
+        .block(
+            Block::default()
+                .title(" Note ")
+                .title_bottom(" Enter: new line | Ctrl-s: save | Esc: cancel ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+    f.render_widget(editor, area);
+
+    // With no room inside the border there is nowhere to put the cursor.
+    if height > 0 && width > 0 {
+        f.set_cursor_position((
+            area.x + 1 + (column - scroll_x) as u16,
+            area.y + 1 + (line - scroll_y) as u16,
+        ));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn render_tag_browser(f: &mut Frame, app: &App, area: Rect) {
+    let browser = &app.tag_browser;
+    let tags: Vec<ListItem> = app
+        .tag_counts()
+        .into_iter()
+        .map(|(tag, count)| {
+            let marked = browser.marked.contains(&tag.to_lowercase());
+            ListItem::new(Line::from(vec![
+                if marked {
+                    Span::styled(" ✔ ", Style::default().fg(Color::Green))
+                } else {
+                    Span::raw("   ")
+                },
+                Span::styled(tag, Style::default().fg(Color::Cyan)),
+                Span::styled(format!(" ({})", count), Style::default().fg(Color::Gray)),
+            ]))
+        })
+        .collect();
+
+    let title = if browser.match_all {
+        " Tags (match all) "
+    } else {
+        " Tags (match any) "
+    };
+    let list = List::new(tags)
+        .block(
+            Block::default()
+                .title(title)
+                .title_bottom(" Space: mark | m: all/any | Enter: filter | Esc: close ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_stateful_widget(
+        list,
+        area,
+        &mut ListState::default().with_selected(Some(browser.cursor)),
+    );
+}
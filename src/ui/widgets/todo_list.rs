@@ -1,4 +1,5 @@
-use crate::App;
+use crate::{app::match_positions, App};
+use chrono::Local;
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -8,11 +9,52 @@ use ratatui::{
 };
 
 pub fn render_todo_list(f: &mut Frame, app: &App, layout: Rect) {
-    let todos: Vec<ListItem> = app
-        .filtered_todos()
-        .iter()
-        .map(|todo| {
+    let now = Local::now().naive_local();
+    let query = app.search_query();
+    let rows = app.visible_rows();
+    let selected = app.selected_row();
+    let marked = app.marked_ids();
+    let mut selected_item = None;
+    let mut todos: Vec<ListItem> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if row.group.is_some() && (i == 0 || rows[i - 1].group != row.group) {
+            let count = rows[i..]
+                .iter()
+                .take_while(|other| other.group == row.group)
+                .count();
+            todos.push(ListItem::new(Line::from(Span::styled(
+                format!(
+                    "── {} ({}) ──",
+                    row.group.as_deref().unwrap_or_default(),
+                    count
+                ),
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            ))));
+        }
+        if selected == Some(i) {
+            selected_item = Some(todos.len());
+        }
+        todos.push({
+            let todo = row.todo;
             let mut spans = vec![
+                if marked.contains(&todo.id) {
+                    Span::styled(
+                        "┃",
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Span::raw(" ")
+                },
+                Span::raw("  ".repeat(row.depth)),
+                match row.progress {
+                    Some(_) if row.collapsed => Span::styled("▸", Style::default().fg(Color::Blue)),
+                    Some(_) => Span::styled("▾", Style::default().fg(Color::Blue)),
+                    None => Span::raw(" "),
+                },
                 if todo.completed {
                     Span::styled(" ✔ ", Style::default().fg(Color::Green))
                 } else {
@@ -30,27 +72,79 @@ pub fn render_todo_list(f: &mut Frame, app: &App, layout: Rect) {
                     }
                 },
                 Span::raw(" "),
-                Span::styled(
-                    &todo.description,
-                    Style::default()
-                        .fg(if todo.completed {
-                            Color::Gray
-                        } else {
-                            Color::White
-                        })
-                        .add_modifier(if todo.completed {
-                            Modifier::CROSSED_OUT
-                        } else {
-                            Modifier::empty()
-                        }),
-                ),
             ];
+            spans.extend(highlighted(
+                &todo.description,
+                query,
+                Style::default()
+                    .fg(if todo.completed {
+                        Color::Gray
+                    } else {
+                        Color::White
+                    })
+                    .add_modifier(if todo.completed {
+                        Modifier::CROSSED_OUT
+                    } else {
+                        Modifier::empty()
+                    }),
+            ));
+
+            if let Some((done, total)) = row.progress {
+                spans.push(Span::styled(
+                    format!(" ({}/{})", done, total),
+                    Style::default().fg(if done == total {
+                        Color::Green
+                    } else {
+                        Color::Yellow
+                    }),
+                ));
+            }
 
             if !todo.tags.is_empty() {
-                spans.push(Span::raw(" "));
+                let style = Style::default().fg(Color::Cyan);
+                spans.push(Span::styled(" [", style));
+                for (i, tag) in todo.tags.iter().enumerate() {
+                    if i > 0 {
+                        spans.push(Span::styled(", ", style));
+                    }
+                    spans.extend(highlighted(tag, query, style));
+                }
+                spans.push(Span::styled("]", style));
+            }
+
+            let blockers = app.open_blockers(todo);
+            if !blockers.is_empty() {
                 spans.push(Span::styled(
-                    format!("[{}]", todo.tags.join(", ")),
-                    Style::default().fg(Color::Cyan),
+                    format!(
+                        " ⧗ blocked by {}",
+                        blockers
+                            .iter()
+                            .map(|id| format!("#{}", id))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+
+            if let Some(due) = todo.due_label() {
+                let color = if todo.is_overdue(now) {
+                    Color::Red
+                } else if todo.is_due_today(now.date()) {
+                    Color::Yellow
+                } else {
+                    Color::Magenta
+                };
+                spans.push(Span::styled(
+                    format!(" due {}", due),
+                    Style::default().fg(color),
+                ));
+            }
+
+            if let Some(recurrence) = &todo.recurrence {
+                spans.push(Span::styled(
+                    format!(" ↻ {}", recurrence.label()),
+                    Style::default().fg(Color::Blue),
                 ));
             }
 
@@ -59,13 +153,13 @@ pub fn render_todo_list(f: &mut Frame, app: &App, layout: Rect) {
             }
 
             spans.push(Span::styled(
-                format!(" ({})", todo.created_at.format("%Y-%m-%d")),
+                format!(" #{} ({})", todo.id, todo.created_at.format("%Y-%m-%d")),
                 Style::default().fg(Color::Gray),
             ));
 
             ListItem::new(Line::from(spans))
-        })
-        .collect();
+        });
+    }
 
     let todos_block = Block::default()
         .borders(Borders::ALL)
@@ -86,6 +180,35 @@ pub fn render_todo_list(f: &mut Frame, app: &App, layout: Rect) {
     f.render_stateful_widget(
         todos,
         layout,
-        &mut ListState::default().with_selected(app.selected_index),
+        &mut ListState::default().with_selected(selected_item),
     );
 }
+
+/// Splits `text` into spans in `style`, with the chars matching the search
+/// `query` highlighted.
+fn highlighted(text: &str, query: &str, style: Style) -> Vec<Span<'static>> {
+    let Some(positions) = match_positions(text, query) else {
+        return vec![Span::styled(text.to_string(), style)];
+    };
+
+    let match_style = style
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut current = String::new();
+    let mut current_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = positions.contains(&i);
+        if matched != current_matched && !current.is_empty() {
+            let style = if current_matched { match_style } else { style };
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+        current_matched = matched;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        let style = if current_matched { match_style } else { style };
+        spans.push(Span::styled(current, style));
+    }
+    spans
+}
@@ -0,0 +1,60 @@
+use crate::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn render_trash(f: &mut Frame, app: &App, area: Rect) {
+    let todos: Vec<ListItem> = app
+        .trashed_todos()
+        .into_iter()
+        .map(|todo| {
+            let deleted_at = todo
+                .deleted_at
+                .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {} ", deleted_at),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::raw(todo.description.clone()),
+            ];
+            match app.trashed_subtasks(todo.id).len() {
+                0 => {}
+                1 => spans.push(Span::styled(
+                    " (+1 subtask)",
+                    Style::default().fg(Color::Gray),
+                )),
+                count => spans.push(Span::styled(
+                    format!(" (+{} subtasks)", count),
+                    Style::default().fg(Color::Gray),
+                )),
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(todos)
+        .block(
+            Block::default()
+                .title(" Trash ")
+                .title_bottom(" r: restore | d: delete | D: empty trash | Esc: close ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_stateful_widget(
+        list,
+        area,
+        &mut ListState::default().with_selected(Some(app.trash_cursor)),
+    );
+}